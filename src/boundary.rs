@@ -0,0 +1,164 @@
+use mesh::*;
+use handle::*;
+use geometry::{sub, norm};
+
+impl Topology {
+    /// Returns `true` if the `Mesh` has no boundary halfedge, i.e. it is watertight.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(3);
+    /// m.add_face(&vvec).unwrap();
+    /// assert!(!m.topology.is_closed());
+    /// ```
+    pub fn is_closed(&self) -> bool {
+        self.halfedges().all(|h| !self.is_boundary_halfedge(h))
+    }
+
+    /// Returns the boundary cycles of the `Mesh`, each one as the ordered list of its halfedges.
+    ///
+    /// Each cycle starts with its boundary halfedge of lowest index and follows the next halfedges,
+    /// so the number of edges of a cycle is its length. The number of cycles is the number of holes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+    /// m.add_face(&vec![vvec[0],vvec[2],vvec[3]]).unwrap();
+    ///
+    /// let loops = m.topology.boundary_loops();
+    /// assert_eq!(loops.len(),1);
+    /// assert_eq!(loops[0].len(),4);
+    /// ```
+    pub fn boundary_loops(&self) -> Vec<Vec<Halfedge>> {
+        let mut visited = vec![false; self.halfedges_size()];
+        let mut loops = Vec::new();
+        for h0 in self.halfedges() {
+            if visited[h0.idx()] || !self.is_boundary_halfedge(h0) {
+                continue;
+            }
+            let mut boundary = Vec::new();
+            let mut h = h0;
+            loop {
+                visited[h.idx()] = true;
+                boundary.push(h);
+                h = self.next_halfedge(h);
+                if h == h0 {
+                    break;
+                }
+            }
+            loops.push(boundary);
+        }
+        loops
+    }
+}
+
+impl Mesh {
+    /// Returns the sum of the lengths of the halfedges of `boundary`, computed from the vertex positions.
+    ///
+    /// Returns `None` if the `Mesh` has no positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pos = m.add_positions::<[f32;3]>().unwrap();
+    /// let vvec = m.add_vertices(3);
+    /// m.properties[(pos,vvec[1])] = [3.,0.,0.];
+    /// m.properties[(pos,vvec[2])] = [0.,4.,0.];
+    /// m.add_face(&vvec).unwrap();
+    ///
+    /// let loops = m.topology.boundary_loops();
+    /// assert_eq!(m.perimeter(&loops[0]),Some(12.));
+    /// ```
+    pub fn perimeter(&self, boundary : &[Halfedge]) -> Option<f64> {
+        let position = match self.coords_reader() {
+            Some(x) => x,
+            None => return None,
+        };
+        Some(boundary.iter().map(|&h| {
+            norm(sub(position(self.topology.to_vertex(h)),position(self.topology.from_vertex(h))))
+        }).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mesh::*;
+    use handle::*;
+
+    /// Builds a 3x3 grid of quads, without the center one.
+    fn grid_with_hole() -> Mesh {
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(16);
+        for i in 0..3 {
+            for j in 0..3 {
+                if i == 1 && j == 1 {
+                    continue;
+                }
+                let v = 4*i + j;
+                m.add_face(&vec![vvec[v],vvec[v+1],vvec[v+5],vvec[v+4]]).unwrap();
+            }
+        }
+        m
+    }
+
+    #[test]
+    fn boundary_loops() {
+        let mut m = grid_with_hole();
+        assert!(!m.topology.is_closed());
+        let loops = m.topology.boundary_loops();
+        assert_eq!(loops.len(),2);
+        let mut lengths : Vec<usize> = loops.iter().map(|l| l.len()).collect();
+        lengths.sort();
+        assert_eq!(lengths,vec![4,12]);
+        for l in &loops {
+            for (i,&h) in l.iter().enumerate() {
+                assert!(m.topology.is_boundary_halfedge(h));
+                assert!(m.topology.next_halfedge(h) == l[(i+1) % l.len()]);
+            }
+        }
+
+        m.delete_face(Face::new(0));
+        assert_eq!(m.topology.boundary_loops().len(),2);
+        m.garbage_collection();
+        assert_eq!(m.topology.boundary_loops().len(),2);
+        assert!(Mesh::new().topology.is_closed());
+    }
+
+    #[cfg(feature = "mesh_io")]
+    #[test]
+    fn closed_mesh() {
+        let m = Mesh::load("cube.obj").ok().unwrap();
+        assert!(m.topology.is_closed());
+        assert!(m.topology.boundary_loops().is_empty());
+    }
+
+    #[test]
+    fn perimeter() {
+        let mut m = grid_with_hole();
+        assert!(m.perimeter(&m.topology.boundary_loops()[0]).is_none());
+        let pos = m.add_positions::<[f64;3]>().unwrap();
+        for i in 0..4 {
+            for j in 0..4 {
+                m.properties[(pos,Vertex::new(4*i + j))] = [j as f64,i as f64,0.];
+            }
+        }
+        m.properties[(pos,Vertex::new(0))] = [0.,-0.1,0.];
+        let mut perimeters : Vec<f64> = m.topology.boundary_loops().iter().map(|l| m.perimeter(l).unwrap()).collect();
+        perimeters.sort_by(|a,b| a.partial_cmp(b).unwrap());
+        let corner = (1. + 0.01f64).sqrt() + 1.1;
+        assert_eq!(perimeters[0],4.);
+        assert!((perimeters[1] - (10. + corner)).abs() < 1e-12);
+    }
+}
@@ -3,13 +3,13 @@ use handle::*;
 /// Store the connectivity of a vertex in a halfedge mesh.
 #[derive(Copy, Clone)]
 pub struct VertexConnectivity {
-    pub halfedge_ : Option<Halfedge>,
+    pub halfedge_ : Halfedge,
 }
 
 /// Store the connectivity of a halfedge in a halfedge mesh.
 #[derive(Copy, Clone)]
 pub struct HalfedgeConnectivity {
-    pub face_ : Option<Face>,
+    pub face_ : Face,
     pub vertex_ : Vertex,
     pub next_halfedge_ : Halfedge,
     pub prev_halfedge_ : Halfedge,
@@ -25,7 +25,7 @@ impl VertexConnectivity {
     /// Constructs an invalid `VertexConnectivity`.
     pub fn new() -> VertexConnectivity {
         VertexConnectivity {
-            halfedge_ : None,
+            halfedge_ : Halfedge::invalid(),
         }
     }
 }
@@ -34,10 +34,10 @@ impl HalfedgeConnectivity {
     /// Constructs an invalid `HalfedgeConnectivity`.
     pub fn new() -> HalfedgeConnectivity {
         HalfedgeConnectivity {
-            face_ : None,
-            vertex_ : Vertex::new(0),
-            next_halfedge_ : Halfedge::new(0),
-            prev_halfedge_ : Halfedge::new(0),
+            face_ : Face::invalid(),
+            vertex_ : Vertex::invalid(),
+            next_halfedge_ : Halfedge::invalid(),
+            prev_halfedge_ : Halfedge::invalid(),
         }
     }
 }
@@ -46,7 +46,7 @@ impl FaceConnectivity {
     /// Constructs an invalid `FaceConnectivity`.
     pub fn new() -> FaceConnectivity {
         FaceConnectivity {
-            halfedge_ : Halfedge::new(0),
+            halfedge_ : Halfedge::invalid(),
         }
     }
 }
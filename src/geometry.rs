@@ -0,0 +1,273 @@
+use std::fmt::Debug;
+use mesh::*;
+use handle::*;
+#[cfg(feature = "nalgebra")]
+use nalgebra::Vector3;
+
+/// A floating point type usable as the coordinates of a `Point`.
+pub trait Scalar : 'static + Copy + Debug + PartialOrd {
+    /// Converts a `f64` into this scalar type.
+    fn from_f64(x : f64) -> Self;
+
+    /// Converts this scalar into a `f64`.
+    fn to_f64(self) -> f64;
+}
+
+impl Scalar for f32 {
+    fn from_f64(x : f64) -> f32 {
+        x as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Scalar for f64 {
+    fn from_f64(x : f64) -> f64 {
+        x
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// A point type usable for the vertex positions of a `Mesh`.
+pub trait Point : 'static + Copy + Debug {
+    type Scalar : Scalar;
+
+    /// Constructs a point from its coordinates.
+    fn from_coords(x : Self::Scalar, y : Self::Scalar, z : Self::Scalar) -> Self;
+
+    /// Returns the coordinates of the point.
+    fn coords(&self) -> [Self::Scalar;3];
+}
+
+impl<S : Scalar> Point for [S;3] {
+    type Scalar = S;
+
+    fn from_coords(x : S, y : S, z : S) -> [S;3] {
+        [x,y,z]
+    }
+
+    fn coords(&self) -> [S;3] {
+        *self
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+macro_rules! impl_vector3_point {
+    ($s:ty) => {
+        impl Point for Vector3<$s> {
+            type Scalar = $s;
+
+            fn from_coords(x : $s, y : $s, z : $s) -> Vector3<$s> {
+                Vector3::new(x,y,z)
+            }
+
+            fn coords(&self) -> [$s;3] {
+                [self.x,self.y,self.z]
+            }
+        }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl_vector3_point!(f32);
+#[cfg(feature = "nalgebra")]
+impl_vector3_point!(f64);
+
+// Operations on coordinates stored as `[f64;3]`.
+
+pub(crate) fn sub(a : [f64;3], b : [f64;3]) -> [f64;3] {
+    [a[0]-b[0],a[1]-b[1],a[2]-b[2]]
+}
+
+pub(crate) fn add(a : [f64;3], b : [f64;3]) -> [f64;3] {
+    [a[0]+b[0],a[1]+b[1],a[2]+b[2]]
+}
+
+pub(crate) fn scale(a : [f64;3], s : f64) -> [f64;3] {
+    [a[0]*s,a[1]*s,a[2]*s]
+}
+
+pub(crate) fn dot(a : [f64;3], b : [f64;3]) -> f64 {
+    a[0]*b[0] + a[1]*b[1] + a[2]*b[2]
+}
+
+pub(crate) fn cross(a : [f64;3], b : [f64;3]) -> [f64;3] {
+    [a[1]*b[2]-a[2]*b[1],a[2]*b[0]-a[0]*b[2],a[0]*b[1]-a[1]*b[0]]
+}
+
+pub(crate) fn norm(a : [f64;3]) -> f64 {
+    dot(a,a).sqrt()
+}
+
+pub(crate) fn normalized(a : [f64;3]) -> [f64;3] {
+    let l = norm(a);
+    if l > 0. {scale(a,1. / l)} else {a}
+}
+
+/// Returns the coordinates of the point `p` as `[f64;3]`.
+pub(crate) fn to_coords<P : Point>(p : P) -> [f64;3] {
+    let c = p.coords();
+    [c[0].to_f64(),c[1].to_f64(),c[2].to_f64()]
+}
+
+/// Returns the point of coordinates `c`.
+pub(crate) fn from_coords<P : Point>(c : [f64;3]) -> P {
+    let s = |x : f64| <P::Scalar as Scalar>::from_f64(x);
+    P::from_coords(s(c[0]),s(c[1]),s(c[2]))
+}
+
+/// Returns the angle between the unit vectors `a` and `b`.
+pub(crate) fn angle(a : [f64;3], b : [f64;3]) -> f64 {
+    dot(a,b).max(-1.).min(1.).acos()
+}
+
+/// Read and write access to vertex positions without looking them up by name.
+pub trait Positions<P : Point> {
+    /// Returns the position of the `Vertex` `v`.
+    fn position(&self, v : Vertex) -> P;
+
+    /// Sets the position of the `Vertex` `v`.
+    fn set_position(&mut self, v : Vertex, p : P);
+}
+
+/// The vertex positions of a `Mesh`, borrowed from its `Properties`.
+///
+/// The position property is looked up once when the `Geometry` is created.
+pub struct Geometry<'a, P : Point> {
+    properties_ : &'a mut Properties,
+    positions_ : PropertyVertex<P>,
+}
+
+impl<'a, P : Point> Geometry<'a, P> {
+    /// Returns the handle of the position property.
+    pub fn handle(&self) -> PropertyVertex<P> {
+        self.positions_
+    }
+}
+
+impl<'a, P : Point> Positions<P> for Geometry<'a, P> {
+    fn position(&self, v : Vertex) -> P {
+        self.properties_[(self.positions_,v)]
+    }
+
+    fn set_position(&mut self, v : Vertex, p : P) {
+        self.properties_[(self.positions_,v)] = p;
+    }
+}
+
+impl Properties {
+    /// Returns the vertex positions stored as `P` in `v:position`, if any.
+    ///
+    /// Borrowing only the `Properties` leaves the `Topology` of the `Mesh` accessible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.add_positions::<[f64;3]>().unwrap();
+    /// let v = m.add_vertex();
+    /// let mut geometry = m.properties.geometry::<[f64;3]>().unwrap();
+    /// geometry.set_position(v,[1.,2.,3.]);
+    /// assert_eq!(geometry.position(v),[1.,2.,3.]);
+    /// ```
+    pub fn geometry<P : Point>(&mut self) -> Option<Geometry<P>> {
+        match self.get_vertex_property::<P>("v:position") {
+            Some(positions) => Some(Geometry {
+                properties_ : self,
+                positions_ : positions,
+            }),
+            None => None,
+        }
+    }
+}
+
+impl Mesh {
+    /// Returns the handle of the vertex positions if they are stored as `P`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.add_positions::<[f64;3]>().unwrap();
+    /// assert!(m.positions::<[f64;3]>().is_some());
+    /// assert!(m.positions::<[f32;3]>().is_none());
+    /// ```
+    pub fn positions<P : Point>(&self) -> Option<PropertyVertex<P>> {
+        self.properties.get_vertex_property::<P>("v:position")
+    }
+
+    /// Adds the vertex positions stored as `P`, initialized at the origin.
+    /// If the `Mesh` already has positions, return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pos = m.add_positions::<[f64;3]>().unwrap();
+    /// let v = m.add_vertex();
+    /// m.properties[(pos,v)] = [0.5,0.,1.];
+    /// assert!(m.add_positions::<[f32;3]>().is_none());
+    /// ```
+    pub fn add_positions<P : Point>(&mut self) -> Option<PropertyVertex<P>> {
+        let zero = <P::Scalar as Scalar>::from_f64(0.);
+        self.properties.add_vertex_property::<P>("v:position",P::from_coords(zero,zero,zero))
+    }
+
+    /// Returns a function reading the vertex positions as `[f64;3]`, whatever their type among
+    /// `[f32;3]`, `[f64;3]` and, with nalgebra, `Vector3<f32>` and `Vector3<f64>`.
+    pub(crate) fn coords_reader<'a>(&'a self) -> Option<Box<Fn(Vertex) -> [f64;3] + 'a>> {
+        let reader = coords_reader::<[f32;3]>(self).or_else(|| coords_reader::<[f64;3]>(self));
+        #[cfg(feature = "nalgebra")]
+        let reader = reader.or_else(|| coords_reader::<Vector3<f32>>(self)).or_else(|| coords_reader::<Vector3<f64>>(self));
+        reader
+    }
+
+    /// Returns `true` if the vertex positions are stored with double precision.
+    #[cfg(feature = "mesh_io")]
+    pub(crate) fn has_double_positions(&self) -> bool {
+        let double = self.positions::<[f64;3]>().is_some();
+        #[cfg(feature = "nalgebra")]
+        let double = double || self.positions::<Vector3<f64>>().is_some();
+        double
+    }
+}
+
+fn coords_reader<'a, P : Point>(m : &'a Mesh) -> Option<Box<Fn(Vertex) -> [f64;3] + 'a>> {
+    match m.positions::<P>() {
+        Some(prop) => Some(Box::new(move |v| to_coords(m.properties[(prop,v)]))),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mesh::Mesh;
+
+    #[test]
+    fn geometry() {
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f64;3]>().unwrap();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        {
+            let mut geometry = m.properties.geometry::<[f64;3]>().unwrap();
+            geometry.set_position(v0,[1.,2.,3.]);
+            geometry.set_position(v1,[0.1,0.2,0.3]);
+        }
+        assert_eq!(m.properties[(pos,v0)],[1.,2.,3.]);
+        assert_eq!(m.properties[(pos,v1)],[0.1,0.2,0.3]);
+        assert!(m.properties.geometry::<[f32;3]>().is_none());
+    }
+}
@@ -1,4 +1,8 @@
 use std::marker::PhantomData;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::usize;
 
 #[derive(Copy, Clone, Debug)]
 pub struct PhantomVertex;
@@ -8,17 +12,15 @@ pub struct PhantomFace;
 pub struct PhantomEdge;
 #[derive(Copy, Clone, Debug)]
 pub struct PhantomHalfedge;
-#[derive(Copy, Clone, Debug)]
-pub struct PhantomProperty;
 
 pub type Vertex = Handle<PhantomVertex>;
 pub type Face = Handle<PhantomFace>;
 pub type Edge = Handle<PhantomEdge>;
 pub type Halfedge = Handle<PhantomHalfedge>;
-pub type PropertyVertex<T> = Handle<(PhantomVertex,T)>;
-pub type PropertyFace<T> = Handle<(PhantomFace,T)>;
-pub type PropertyEdge<T> = Handle<(PhantomEdge,T)>;
-pub type PropertyHalfedge<T> = Handle<(PhantomHalfedge,T)>;
+pub type PropertyVertex<T> = PropertyHandle<(PhantomVertex,T)>;
+pub type PropertyFace<T> = PropertyHandle<(PhantomFace,T)>;
+pub type PropertyEdge<T> = PropertyHandle<(PhantomEdge,T)>;
+pub type PropertyHalfedge<T> = PropertyHandle<(PhantomHalfedge,T)>;
 
 /// A basic handle
 ///
@@ -60,6 +62,48 @@ impl<A> Handle<A> {
     pub fn idx(&self) -> usize {
         self.index_
     }
+
+    /// Constructs an invalid `Handle<A>`, which refers to no element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Vertex;
+    ///
+    /// let v = Vertex::invalid();
+    /// assert!(!v.is_valid());
+    /// ```
+    pub fn invalid() -> Handle<A> {
+        Handle::new(usize::MAX)
+    }
+
+    /// Returns `true` if the given `Handle<A>` is not the invalid handle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Vertex;
+    ///
+    /// assert!(Vertex::new(3).is_valid());
+    /// assert!(!Vertex::invalid().is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        self.index_ != usize::MAX
+    }
+
+    /// Returns the given `Handle<A>` if it is valid, `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Vertex;
+    ///
+    /// assert!(Vertex::new(3).as_option() == Some(Vertex::new(3)));
+    /// assert!(Vertex::invalid().as_option().is_none());
+    /// ```
+    pub fn as_option(&self) -> Option<Handle<A>> {
+        if self.is_valid() {Some(Handle::new(self.index_))} else {None}
+    }
 }
 
 impl<A> PartialEq for Handle<A> {
@@ -69,6 +113,122 @@ impl<A> PartialEq for Handle<A> {
 }
 impl<A> Eq for Handle<A> { }
 
+impl<A> PartialOrd for Handle<A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<A> Ord for Handle<A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index_.cmp(&other.index_)
+    }
+}
+
+impl<A> Hash for Handle<A> {
+    fn hash<S : Hasher>(&self, state: &mut S) {
+        self.index_.hash(state);
+    }
+}
+
+/// Writes a handle as its prefix followed by its index, like `v12`, or `v?` if it is invalid.
+fn fmt_handle<A>(h : &Handle<A>, prefix : &str, f : &mut fmt::Formatter) -> fmt::Result {
+    if h.is_valid() {
+        write!(f,"{}{}",prefix,h.index_)
+    } else {
+        write!(f,"{}?",prefix)
+    }
+}
+
+impl fmt::Display for Vertex {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        fmt_handle(self,"v",f)
+    }
+}
+
+impl fmt::Display for Face {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        fmt_handle(self,"f",f)
+    }
+}
+
+impl fmt::Display for Edge {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        fmt_handle(self,"e",f)
+    }
+}
+
+impl fmt::Display for Halfedge {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        fmt_handle(self,"h",f)
+    }
+}
+
+/// A handle to a property
+///
+/// Besides the index of the property, `PropertyHandle<A>` stores the id of the container which
+/// created it and the generation of the property, so that using a handle of another mesh or of
+/// a removed property is detected.
+#[derive(Debug)]
+pub struct PropertyHandle<A> {
+    type_ : PhantomData<A>,
+    index_ : usize,
+    container_ : usize,
+    generation_ : usize,
+}
+
+impl<A> PropertyHandle<A> {
+    /// Constructs a new `PropertyHandle<A>`, only containers create property handles.
+    pub(crate) fn new(idx : usize, container : usize, generation : usize) -> PropertyHandle<A> {
+        PropertyHandle {
+            type_ : PhantomData,
+            index_ : idx,
+            container_ : container,
+            generation_ : generation,
+        }
+    }
+
+    /// Returns the index of the given `PropertyHandle<A>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pv = m.properties.add_vertex_property::<u32>("v:my_prop",17).unwrap();
+    /// assert_eq!(pv.idx(),0);
+    /// ```
+    pub fn idx(&self) -> usize {
+        self.index_
+    }
+
+    /// Returns the id of the container which created the given `PropertyHandle<A>`.
+    pub(crate) fn container(&self) -> usize {
+        self.container_
+    }
+
+    /// Returns the generation of the property when the given `PropertyHandle<A>` was created.
+    pub(crate) fn generation(&self) -> usize {
+        self.generation_
+    }
+}
+
+// Implemented by hand so that handles are `Copy` whatever the type of the property.
+impl<A> Clone for PropertyHandle<A> {
+    fn clone(&self) -> PropertyHandle<A> {
+        *self
+    }
+}
+impl<A> Copy for PropertyHandle<A> { }
+
+impl<A> PartialEq for PropertyHandle<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index_ == other.index_ && self.container_ == other.container_ && self.generation_ == other.generation_
+    }
+}
+impl<A> Eq for PropertyHandle<A> { }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,4 +251,27 @@ mod tests {
         assert!(h1!=h2);
         assert!(h1==h3);
     }
+
+    #[test]
+    fn ord_and_hash() {
+        use std::collections::{BTreeSet, HashSet};
+        let mut vvec = vec![Vertex::new(3),Vertex::new(1),Vertex::new(2),Vertex::new(1)];
+        vvec.sort();
+        assert!(vvec == vec![Vertex::new(1),Vertex::new(1),Vertex::new(2),Vertex::new(3)]);
+        assert!(Vertex::new(1) < Vertex::new(2));
+        assert!(Vertex::invalid() > Vertex::new(2));
+        let hset : HashSet<Vertex> = vvec.iter().cloned().collect();
+        assert_eq!(hset.len(),3);
+        let bset : BTreeSet<Vertex> = vvec.iter().cloned().collect();
+        assert!(bset.iter().next() == Some(&Vertex::new(1)));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(format!("{}",Vertex::new(12)),"v12");
+        assert_eq!(format!("{}",Halfedge::new(7)),"h7");
+        assert_eq!(format!("{}",Edge::new(0)),"e0");
+        assert_eq!(format!("{}",Face::new(5)),"f5");
+        assert_eq!(format!("{}",Face::invalid()),"f?");
+    }
 }
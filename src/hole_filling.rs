@@ -0,0 +1,523 @@
+use std::collections::HashSet;
+use std::f64::consts::PI;
+use mesh::*;
+use handle::*;
+use geometry::{Point, to_coords, from_coords, sub, add, scale, cross, norm, normalized, angle};
+use mesh_iterator::{VerticesAround, HalfedgesAround};
+
+/// Options of `Mesh::fill_hole`, both disabled by default.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct HoleFillingOptions {
+    /// Splits and flips the edges of the patch until they match the length of the boundary edges.
+    pub refine : bool,
+    /// Moves the vertices inserted by the refinement to minimize the curvature variation of the patch.
+    pub fair : bool,
+}
+
+/// The errors of `Mesh::fill_hole`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HoleFillingError {
+    /// The halfedge is not a boundary halfedge of the `Mesh`.
+    NotBoundary(Halfedge),
+    /// The `Mesh` has no vertex positions of the requested type.
+    MissingPosition,
+    /// A vertex appears several times on the boundary of the hole.
+    NonManifoldHole,
+    /// Every triangulation of the hole would duplicate an existing edge.
+    NoTriangulation,
+}
+
+/// The weight of a triangulation: its largest dihedral angle, then its area.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+struct Weight {
+    angle : f64,
+    area : f64,
+}
+
+impl Weight {
+    fn zero() -> Weight {
+        Weight {
+            angle : 0.,
+            area : 0.,
+        }
+    }
+
+    fn infinite() -> Weight {
+        Weight {
+            angle : f64::INFINITY,
+            area : f64::INFINITY,
+        }
+    }
+
+    fn add(self, w : Weight) -> Weight {
+        Weight {
+            angle : self.angle.max(w.angle),
+            area : self.area + w.area,
+        }
+    }
+}
+
+/// Returns the length of the `Halfedge` h.
+fn edge_length(m : &Mesh, position : &Fn(Vertex) -> [f64;3], h : Halfedge) -> f64 {
+    norm(sub(position(m.topology.to_vertex(h)),position(m.topology.from_vertex(h))))
+}
+
+/// Returns the unit normal of the `Face` f, with Newell's method.
+fn face_normal(m : &Mesh, position : &Fn(Vertex) -> [f64;3], f : Face) -> [f64;3] {
+    let mut n = [0.;3];
+    for h in m.topology.halfedges_around(f) {
+        n = add(n,cross(position(m.topology.from_vertex(h)),position(m.topology.to_vertex(h))));
+    }
+    normalized(n)
+}
+
+/// Returns the uniform Laplacian of the positions at the `Vertex` v, and the valence of v.
+fn uniform_laplacian(m : &Mesh, position : &Fn(Vertex) -> [f64;3], v : Vertex) -> ([f64;3],f64) {
+    let mut sum = [0.;3];
+    let mut n = 0.;
+    for w in m.topology.vertices_around(v) {
+        sum = add(sum,position(w));
+        n += 1.;
+    }
+    (sub(scale(sum,1. / n),position(v)),n)
+}
+
+/// The minimum weight triangulation of a hole, in the spirit of Liepa's "Filling holes in meshes".
+struct Triangulation<'a> {
+    mesh_ : &'a Mesh,
+    position_ : &'a Fn(Vertex) -> [f64;3],
+    hole_ : &'a [Halfedge],
+    vertices_ : Vec<Vertex>,
+    positions_ : Vec<[f64;3]>,
+    weights_ : Vec<Weight>,
+    index_ : Vec<usize>,
+}
+
+impl<'a> Triangulation<'a> {
+    fn new(mesh : &'a Mesh, position : &'a Fn(Vertex) -> [f64;3], hole : &'a [Halfedge]) -> Triangulation<'a> {
+        let vertices : Vec<Vertex> = hole.iter().map(|&h| mesh.topology.from_vertex(h)).collect();
+        let positions = vertices.iter().map(|&v| position(v)).collect();
+        let n = hole.len();
+        Triangulation {
+            mesh_ : mesh,
+            position_ : position,
+            hole_ : hole,
+            vertices_ : vertices,
+            positions_ : positions,
+            weights_ : vec![Weight::zero(); n * n],
+            index_ : vec![0; n * n],
+        }
+    }
+
+    /// Returns `true` if the vertices `i` and `j` of the hole are already connected by an edge
+    /// which is not an edge of the hole.
+    fn is_existing_edge(&self, i : usize, j : usize) -> bool {
+        let n = self.vertices_.len();
+        if j == i+1 || (i == 0 && j == n-1) {
+            return false;
+        }
+        self.mesh_.topology.find_halfedge(self.vertices_[i],self.vertices_[j]).is_some()
+    }
+
+    fn triangle_normal(&self, i : usize, j : usize, k : usize) -> [f64;3] {
+        let p = &self.positions_;
+        normalized(cross(sub(p[j],p[i]),sub(p[k],p[i])))
+    }
+
+    /// Returns the normal of the face across the `i`th edge of the hole, if any.
+    fn outer_normal(&self, i : usize) -> Option<[f64;3]> {
+        self.mesh_.topology.face(self.mesh_.topology.opposite_halfedge(self.hole_[i]))
+            .map(|f| face_normal(self.mesh_,self.position_,f))
+    }
+
+    /// Returns the normal of the triangle built on the edge `(i,j)` of the partial triangulation
+    /// or, for an edge of the hole, of the face across it.
+    fn neighbor_normal(&self, i : usize, j : usize) -> Option<[f64;3]> {
+        let n = self.vertices_.len();
+        if j == i+1 {
+            self.outer_normal(i)
+        } else {
+            Some(self.triangle_normal(i,self.index_[i*n + j],j))
+        }
+    }
+
+    fn weight(&self, i : usize, m : usize, k : usize) -> Weight {
+        if self.is_existing_edge(i,m) || self.is_existing_edge(m,k) || self.is_existing_edge(i,k) {
+            return Weight::infinite();
+        }
+        let p = &self.positions_;
+        let area = 0.5 * norm(cross(sub(p[m],p[i]),sub(p[k],p[i])));
+
+        let n = self.vertices_.len();
+        let normal = self.triangle_normal(i,m,k);
+        let mut neighbors = vec![self.neighbor_normal(i,m),self.neighbor_normal(m,k)];
+        if i == 0 && k == n-1 {
+            neighbors.push(self.outer_normal(n-1));
+        }
+        let angle = neighbors.iter().filter_map(|x| *x).map(|x| angle(normal,x)).fold(0.,f64::max);
+
+        Weight {
+            angle : angle,
+            area : area,
+        }
+    }
+
+    /// Computes the optimal triangulation, as triangles of indices in the hole.
+    fn compute(&mut self) -> Option<Vec<[usize;3]>> {
+        let n = self.vertices_.len();
+        for gap in 2..n {
+            for i in 0..n-gap {
+                let k = i + gap;
+                let mut best = Weight::infinite();
+                let mut best_m = None;
+                for m in i+1..k {
+                    let w = self.weights_[i*n + m].add(self.weights_[m*n + k]).add(self.weight(i,m,k));
+                    if w < best {
+                        best = w;
+                        best_m = Some(m);
+                    }
+                }
+                self.weights_[i*n + k] = best;
+                self.index_[i*n + k] = best_m.unwrap_or(i+1);
+            }
+        }
+        if self.weights_[n-1] == Weight::infinite() {
+            return None;
+        }
+
+        let mut triangles = Vec::new();
+        let mut stack = vec![(0,n-1)];
+        while let Some((i,k)) = stack.pop() {
+            if k < i+2 {
+                continue;
+            }
+            let m = self.index_[i*n + k];
+            triangles.push([i,m,k]);
+            stack.push((i,m));
+            stack.push((m,k));
+        }
+        Some(triangles)
+    }
+}
+
+impl Mesh {
+    /// Fills the hole bounded by the boundary `Halfedge` h with triangles, and returns them.
+    ///
+    /// The triangulation minimizes the largest dihedral angle between adjacent triangles, then
+    /// the area, among the triangulations which connect only the vertices of the hole. The
+    /// vertex positions must be stored as `P`. If a triangle can not be added, the triangles
+    /// added before it are deleted so that the hole is left open. They are only marked as deleted,
+    /// so `garbage_collection` must be called to remove them after an `Err(NoTriangulation)`.
+    ///
+    /// With `options.refine`, the patch is split until its edges are about as long as the edges
+    /// of the hole, so it matches the surrounding density. With `options.fair`, the inserted
+    /// vertices are then moved to minimize the curvature variation across the patch. The other
+    /// properties of the inserted vertices keep their default value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pos = m.add_positions::<[f32;3]>().unwrap();
+    /// let vvec = m.add_vertices(5);
+    /// m.properties[(pos,vvec[1])] = [1.,0.,0.];
+    /// m.properties[(pos,vvec[2])] = [1.,1.,0.];
+    /// m.properties[(pos,vvec[3])] = [0.,1.,0.];
+    /// m.properties[(pos,vvec[4])] = [0.5,0.5,1.];
+    /// for i in 0..4 {
+    ///     m.add_face(&vec![vvec[i],vvec[(i+1) % 4],vvec[4]]).unwrap();
+    /// }
+    ///
+    /// let h = m.topology.boundary_loops()[0][0];
+    /// let faces = m.fill_hole::<[f32;3]>(h,HoleFillingOptions::default()).unwrap();
+    /// assert_eq!(faces.len(),2);
+    /// assert!(m.topology.is_closed());
+    /// ```
+    pub fn fill_hole<P : Point>(&mut self, h : Halfedge, options : HoleFillingOptions) -> Result<Vec<Face>,HoleFillingError> {
+        if h.idx() >= self.topology.halfedges_size() || self.topology.is_deleted_halfedge(h) || !self.topology.is_boundary_halfedge(h) {
+            return Err(HoleFillingError::NotBoundary(h));
+        }
+        let pos = match self.positions::<P>() {
+            Some(pos) => pos,
+            None => return Err(HoleFillingError::MissingPosition),
+        };
+
+        let mut hole = Vec::new();
+        let mut seen = HashSet::new();
+        let mut hh = h;
+        loop {
+            if !seen.insert(self.topology.from_vertex(hh)) {
+                return Err(HoleFillingError::NonManifoldHole);
+            }
+            hole.push(hh);
+            hh = self.topology.next_halfedge(hh);
+            if hh == h {
+                break;
+            }
+        }
+
+        let (triangles,length) = {
+            let position = |v| to_coords(self.properties[(pos,v)]);
+            let length = hole.iter().map(|&h| edge_length(self,&position,h)).sum::<f64>() / hole.len() as f64;
+            match Triangulation::new(self,&position,&hole).compute() {
+                Some(t) => (t,length),
+                None => return Err(HoleFillingError::NoTriangulation),
+            }
+        };
+        let vertices : Vec<Vertex> = hole.iter().map(|&h| self.topology.from_vertex(h)).collect();
+        let mut faces = Vec::new();
+        for t in triangles {
+            match self.add_face(&vec![vertices[t[0]],vertices[t[1]],vertices[t[2]]]) {
+                Some(f) => faces.push(f),
+                None => {
+                    // leave the hole as it was
+                    for &f in faces.iter().rev() {
+                        self.delete_face(f);
+                    }
+                    return Err(HoleFillingError::NoTriangulation);
+                },
+            }
+        }
+
+        if options.refine {
+            let first_vertex = self.topology.vertices_size();
+            self.refine_patch(pos,&mut faces,length);
+            let inserted : Vec<Vertex> = (first_vertex..self.topology.vertices_size()).map(Vertex::new).collect();
+            if options.fair {
+                self.fair_patch(pos,&inserted,length);
+            }
+        }
+        Ok(faces)
+    }
+
+    /// Returns the edges whose two incident faces belong to the patch.
+    fn patch_edges(&self, faces : &[Face], in_patch : &[bool]) -> Vec<Edge> {
+        let mut edges = Vec::new();
+        for &f in faces {
+            for h in self.topology.halfedges_around(f) {
+                let o = self.topology.opposite_halfedge(h);
+                // each inner edge is found from the face of its halfedge of lowest index
+                match self.topology.face(o) {
+                    Some(g) if in_patch[g.idx()] && h.idx() < o.idx() => edges.push(self.topology.edge(h)),
+                    _ => (),
+                }
+            }
+        }
+        edges
+    }
+
+    /// Returns `true` if the sum of the angles opposite to the `Edge` e exceeds pi.
+    fn is_delaunay_flip<P : Point>(&self, pos : PropertyVertex<P>, e : Edge) -> bool {
+        let position = |v| to_coords(self.properties[(pos,v)]);
+        let opposite_angle = |h : Halfedge| {
+            let n = self.topology.next_halfedge(h);
+            let p = position(self.topology.to_vertex(n));
+            let a = normalized(sub(position(self.topology.from_vertex(h)),p));
+            let b = normalized(sub(position(self.topology.to_vertex(h)),p));
+            angle(a,b)
+        };
+        opposite_angle(self.topology.edge_halfedge(e,0)) + opposite_angle(self.topology.edge_halfedge(e,1)) > PI + 1e-9
+    }
+
+    /// Splits the long edges of the patch, flips its edges to improve the triangles
+    /// and smooths the inserted vertices, whose positions are stored in `pos`.
+    fn refine_patch<P : Point>(&mut self, pos : PropertyVertex<P>, faces : &mut Vec<Face>, length : f64) {
+        let first_vertex = self.topology.vertices_size();
+        let mut in_patch = vec![false; self.topology.faces_size()];
+        for &f in faces.iter() {
+            in_patch[f.idx()] = true;
+        }
+
+        for _ in 0..10 {
+            // splitting an edge does not move the endpoints of the other edges
+            let splits : Vec<(Edge,[f64;3])> = {
+                let position = |v| to_coords(self.properties[(pos,v)]);
+                self.patch_edges(faces,&in_patch).into_iter().filter_map(|e| {
+                    let h = self.topology.edge_halfedge(e,0);
+                    if edge_length(self,&position,h) > 4. / 3. * length {
+                        let p0 = position(self.topology.from_vertex(h));
+                        let p1 = position(self.topology.to_vertex(h));
+                        Some((e,scale(add(p0,p1),0.5)))
+                    } else {
+                        None
+                    }
+                }).collect()
+            };
+            let split = !splits.is_empty();
+            for (e,p) in splits {
+                let v = self.add_vertex();
+                self.properties[(pos,v)] = from_coords(p);
+                let first_face = self.topology.faces_size();
+                self.split_edge(e,v);
+                for f in first_face..self.topology.faces_size() {
+                    faces.push(Face::new(f));
+                }
+                in_patch.resize(self.topology.faces_size(),true);
+            }
+
+            for e in self.patch_edges(faces,&in_patch) {
+                if self.topology.is_flip_ok(e) && self.is_delaunay_flip(pos,e) {
+                    self.topology.flip(e);
+                }
+            }
+
+            for v in first_vertex..self.topology.vertices_size() {
+                let v = Vertex::new(v);
+                let p = {
+                    let position = |v| to_coords(self.properties[(pos,v)]);
+                    let (l,n) = uniform_laplacian(self,&position,v);
+                    if n > 0. {Some(add(position(v),l))} else {None}
+                };
+                if let Some(p) = p {
+                    self.properties[(pos,v)] = from_coords(p);
+                }
+            }
+
+            if !split {
+                break;
+            }
+        }
+    }
+
+    /// Moves the vertices `free`, whose positions are stored in `pos`, so that the uniform
+    /// bi-Laplacian of the positions vanishes on them, with Gauss-Seidel iterations.
+    fn fair_patch<P : Point>(&mut self, pos : PropertyVertex<P>, free : &[Vertex], length : f64) {
+        for _ in 0..1000 {
+            let mut displacement = 0f64;
+            for &v in free {
+                let p = {
+                    let position = |v| to_coords(self.properties[(pos,v)]);
+                    let (lv,nv) = uniform_laplacian(self,&position,v);
+                    let mut sum = [0.;3];
+                    let mut diagonal = 1.;
+                    for w in self.topology.vertices_around(v) {
+                        let (lw,nw) = uniform_laplacian(self,&position,w);
+                        sum = add(sum,lw);
+                        diagonal += 1. / (nv * nw);
+                    }
+                    let bilaplacian = sub(scale(sum,1. / nv),lv);
+                    let d = scale(bilaplacian,-1. / diagonal);
+                    displacement = displacement.max(norm(d));
+                    add(position(v),d)
+                };
+                self.properties[(pos,v)] = from_coords(p);
+            }
+            if displacement < 1e-6 * length {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mesh::Mesh;
+
+    /// Builds a grid of `n`x`n` quads on the plane z = 0, without the quads at the center.
+    fn grid_with_hole(n : usize) -> Mesh {
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f64;3]>().unwrap();
+        let vvec = m.add_vertices((n+1) * (n+1));
+        for i in 0..n+1 {
+            for j in 0..n+1 {
+                m.properties[(pos,vvec[i*(n+1) + j])] = [j as f64,i as f64,0.];
+            }
+        }
+        for i in 0..n {
+            for j in 0..n {
+                if i > 0 && i < n-1 && j > 0 && j < n-1 {
+                    continue;
+                }
+                let v = i*(n+1) + j;
+                m.add_face(&vec![vvec[v],vvec[v+1],vvec[v+n+2],vvec[v+n+1]]).unwrap();
+            }
+        }
+        m
+    }
+
+    fn inner_hole(m : &Mesh) -> Halfedge {
+        let loops = m.topology.boundary_loops();
+        assert_eq!(loops.len(),2);
+        if loops[0].len() < loops[1].len() {loops[0][0]} else {loops[1][0]}
+    }
+
+    #[test]
+    fn fill_planar_hole() {
+        let mut m = grid_with_hole(5);
+        let h = inner_hole(&m);
+        let faces = m.fill_hole::<[f64;3]>(h,HoleFillingOptions::default()).unwrap();
+        assert_eq!(faces.len(),10);
+        assert_eq!(m.topology.boundary_loops().len(),1);
+        assert_eq!(m.topology.validate().errors,vec![]);
+        let pos = m.positions::<[f64;3]>().unwrap();
+        for v in m.topology.vertices() {
+            assert_eq!(m.properties[(pos,v)][2],0.);
+        }
+    }
+
+    #[test]
+    fn fill_refined_hole() {
+        let mut m = grid_with_hole(7);
+        let n_vertices = m.topology.n_vertices();
+        let h = inner_hole(&m);
+        let options = HoleFillingOptions {
+            refine : true,
+            fair : true,
+        };
+        let faces = m.fill_hole::<[f64;3]>(h,options).unwrap();
+        assert_eq!(m.topology.boundary_loops().len(),1);
+        assert_eq!(m.topology.validate().errors,vec![]);
+        assert!(m.topology.n_vertices() > n_vertices);
+        assert_eq!(faces.len(),faces.iter().filter(|&&f| !m.topology.is_deleted_face(f)).count());
+
+        let pos = m.positions::<[f64;3]>().unwrap();
+        for v in m.topology.vertices() {
+            let p = m.properties[(pos,v)];
+            assert!(p[2].abs() < 1e-6);
+            assert!(p[0] > 0.5 && p[0] < 6.5 || v.idx() < n_vertices);
+        }
+    }
+
+    #[test]
+    fn fill_hole_errors() {
+        let mut m = grid_with_hole(5);
+        let h = inner_hole(&m);
+        let o = m.topology.opposite_halfedge(h);
+        assert_eq!(m.fill_hole::<[f64;3]>(o,HoleFillingOptions::default()),Err(HoleFillingError::NotBoundary(o)));
+        assert_eq!(m.fill_hole::<[f32;3]>(h,HoleFillingOptions::default()),Err(HoleFillingError::MissingPosition));
+    }
+
+    #[test]
+    fn fair_curved_hole() {
+        let mut m = grid_with_hole(9);
+        let pos = m.positions::<[f64;3]>().unwrap();
+        let n_vertices = m.topology.n_vertices();
+        let vertices : Vec<Vertex> = m.topology.vertices().collect();
+        for v in vertices {
+            let p = m.properties[(pos,v)];
+            m.properties[(pos,v)][2] = 2. - 0.1 * ((p[0]-4.5)*(p[0]-4.5) + (p[1]-4.5)*(p[1]-4.5));
+        }
+        // the vertices at the center of the grid are isolated
+        let boundary_max = m.topology.vertices().filter(|&v| m.topology.halfedge(v).is_some()).map(|v| m.properties[(pos,v)][2]).fold(0.,f64::max);
+        let h = inner_hole(&m);
+        let options = HoleFillingOptions {
+            refine : true,
+            fair : true,
+        };
+        m.fill_hole::<[f64;3]>(h,options).unwrap();
+        assert_eq!(m.topology.validate().errors,vec![]);
+
+        // the fairing continues the curvature of the surrounding surface
+        let inner_max = m.topology.vertices().filter(|v| v.idx() >= n_vertices).map(|v| m.properties[(pos,v)][2]).fold(0.,f64::max);
+        assert!(inner_max > boundary_max + 0.5);
+        for e in m.topology.edges() {
+            let h = m.topology.edge_halfedge(e,0);
+            let a = m.properties[(pos,m.topology.from_vertex(h))];
+            let b = m.properties[(pos,m.topology.to_vertex(h))];
+            assert!(norm(sub(a,b)) < 2.);
+        }
+    }
+}
@@ -2,12 +2,15 @@
 extern crate lwobj;
 #[cfg(feature = "nalgebra")]
 extern crate nalgebra;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
 mod handle;
 pub use handle::Vertex;
 pub use handle::Face;
 pub use handle::Edge;
 pub use handle::Halfedge;
+pub use handle::PropertyHandle;
 pub use handle::PropertyVertex;
 pub use handle::PropertyFace;
 pub use handle::PropertyEdge;
@@ -16,12 +19,47 @@ pub use handle::PropertyHalfedge;
 mod connectivity;
 mod property;
 mod mesh;
+mod geometry;
+mod validation;
+mod boundary;
+mod hole_filling;
+mod triangulation;
 #[cfg(feature = "mesh_io")]
 mod mesh_io;
+#[cfg(feature = "mesh_io")]
+mod ply;
+#[cfg(feature = "mesh_io")]
+mod stl;
+#[cfg(feature = "mesh_io")]
+mod off;
+#[cfg(feature = "nalgebra")]
+mod normals;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "mesh_io")]
+pub use mesh_io::MeshLoadingError;
+#[cfg(feature = "mesh_io")]
+pub use ply::PlyFormat;
+#[cfg(feature = "mesh_io")]
+pub use stl::StlFormat;
+#[cfg(feature = "nalgebra")]
+pub use normals::NormalWeighting;
 pub use mesh::Mesh;
 pub use mesh::Topology;
 pub use mesh::Properties;
+pub use mesh::PropertyElement;
+pub use property::PropertyError;
+pub use validation::TopologyError;
+pub use validation::ValidationReport;
+pub use hole_filling::HoleFillingOptions;
+pub use hole_filling::HoleFillingError;
+pub use geometry::Scalar;
+pub use geometry::Point;
+pub use geometry::Positions;
+pub use geometry::Geometry;
 pub mod mesh_iterator;
 pub use mesh_iterator::VerticesAround;
 pub use mesh_iterator::HalfedgesAround;
 pub use mesh_iterator::FacesAround;
+pub use mesh_iterator::EdgesAround;
+pub mod subdivision;
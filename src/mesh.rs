@@ -3,11 +3,22 @@ use handle::*;
 use connectivity::*;
 use std::ops::Index;
 use std::ops::IndexMut;
+use mesh_iterator::VerticesAround;
+use mesh_iterator::HalfedgesAround;
+use mesh_iterator::FacesAround;
 
+#[derive(Clone)]
 pub struct Topology {
     vconn_ : PropertyVec<Vertex,VertexConnectivity>,
     hconn_ : PropertyVec<Halfedge,HalfedgeConnectivity>,
     fconn_ : PropertyVec<Face,FaceConnectivity>,
+    vdeleted_ : PropertyVec<Vertex,bool>,
+    edeleted_ : PropertyVec<Edge,bool>,
+    fdeleted_ : PropertyVec<Face,bool>,
+    deleted_vertices_ : usize,
+    deleted_edges_ : usize,
+    deleted_faces_ : usize,
+    garbage_ : bool,
 }
 
 impl Topology {
@@ -16,9 +27,100 @@ impl Topology {
             vconn_ : PropertyVec::new(VertexConnectivity::new()),
             hconn_ : PropertyVec::new(HalfedgeConnectivity::new()),
             fconn_ : PropertyVec::new(FaceConnectivity::new()),
+            vdeleted_ : PropertyVec::new(false),
+            edeleted_ : PropertyVec::new(false),
+            fdeleted_ : PropertyVec::new(false),
+            deleted_vertices_ : 0,
+            deleted_edges_ : 0,
+            deleted_faces_ : 0,
+            garbage_ : false,
         }
     }
 
+    /// Returns the number of vertices stored in the `Mesh`, including the deleted ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let v = m.add_vertex();
+    /// m.add_vertex();
+    /// m.delete_vertex(v);
+    /// assert!(m.topology.n_vertices() == 1);
+    /// assert!(m.topology.vertices_size() == 2);
+    /// ```
+    pub fn vertices_size(& self) -> usize {
+        self.vconn_.len()
+    }
+
+    /// Returns the number of faces stored in the `Mesh`, including the deleted ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///    vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vvec).unwrap();
+    /// m.delete_face(f);
+    /// assert!(m.topology.n_faces() == 0);
+    /// assert!(m.topology.faces_size() == 1);
+    /// ```
+    pub fn faces_size(& self) -> usize {
+        self.fconn_.len()
+    }
+
+    /// Returns the number of edges stored in the `Mesh`, including the deleted ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///    vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vvec).unwrap();
+    /// m.delete_face(f);
+    /// assert!(m.topology.n_edges() == 0);
+    /// assert!(m.topology.edges_size() == 3);
+    /// ```
+    pub fn edges_size(& self) -> usize {
+        self.hconn_.len()/2
+    }
+
+    /// Returns the number of halfedges stored in the `Mesh`, including the deleted ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///    vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vvec).unwrap();
+    /// m.delete_face(f);
+    /// assert!(m.topology.n_halfedges() == 0);
+    /// assert!(m.topology.halfedges_size() == 6);
+    /// ```
+    pub fn halfedges_size(& self) -> usize {
+        self.hconn_.len()
+    }
+
     /// Returns the number of vertices in the `Mesh`.
     ///
     /// # Examples
@@ -32,7 +134,7 @@ impl Topology {
     /// assert!(m.topology.n_vertices() == 2);
     /// ```
     pub fn n_vertices(& self) -> usize {
-        self.vconn_.len()
+        self.vertices_size() - self.deleted_vertices_
     }
 
     /// Returns the number of faces in the `Mesh`
@@ -52,7 +154,7 @@ impl Topology {
     /// assert!(m.topology.n_faces() == 1);
     /// ```
     pub fn n_faces(& self) -> usize {
-        self.fconn_.len()
+        self.faces_size() - self.deleted_faces_
     }
 
     /// Returns the number of edges in the `Mesh`
@@ -72,7 +174,7 @@ impl Topology {
     /// assert!(m.topology.n_edges() == 3);
     /// ```
     pub fn n_edges(& self) -> usize {
-        self.hconn_.len()/2
+        self.edges_size() - self.deleted_edges_
     }
 
     /// Returns the number of halfedges in the `Mesh`
@@ -92,7 +194,7 @@ impl Topology {
     /// assert!(m.topology.n_halfedges() == 6);
     /// ```
     pub fn n_halfedges(& self) -> usize {
-        self.hconn_.len()
+        self.halfedges_size() - 2*self.deleted_edges_
     }
 
     /// Returns if the `Vertex` is on a boundary
@@ -160,6 +262,109 @@ impl Topology {
         self.face(self.edge_halfedge(e,0)).is_none() || self.face(self.edge_halfedge(e,1)).is_none()
     }
 
+    /// Returns if the `Vertex` has been deleted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let v = m.add_vertex();
+    /// assert!(!m.topology.is_deleted_vertex(v));
+    /// m.delete_vertex(v);
+    /// assert!(m.topology.is_deleted_vertex(v));
+    /// ```
+    pub fn is_deleted_vertex(&self, v : Vertex) -> bool {
+        self.vdeleted_[v]
+    }
+
+    /// Returns if the `Halfedge` has been deleted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vvec).unwrap();
+    /// let h = m.topology.find_halfedge(vvec[0],vvec[1]).unwrap();
+    /// m.delete_face(f);
+    /// assert!(m.topology.is_deleted_halfedge(h));
+    /// ```
+    pub fn is_deleted_halfedge(&self, h : Halfedge) -> bool {
+        self.edeleted_[self.edge(h)]
+    }
+
+    /// Returns if the `Edge` has been deleted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// m.add_face(&vvec);
+    /// let e = m.topology.edge(m.topology.find_halfedge(vvec[0],vvec[1]).unwrap());
+    /// assert!(!m.topology.is_deleted_edge(e));
+    /// m.delete_edge(e);
+    /// assert!(m.topology.is_deleted_edge(e));
+    /// ```
+    pub fn is_deleted_edge(&self, e : Edge) -> bool {
+        self.edeleted_[e]
+    }
+
+    /// Returns if the `Face` has been deleted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vvec).unwrap();
+    /// assert!(!m.topology.is_deleted_face(f));
+    /// m.delete_face(f);
+    /// assert!(m.topology.is_deleted_face(f));
+    /// ```
+    pub fn is_deleted_face(&self, f : Face) -> bool {
+        self.fdeleted_[f]
+    }
+
+    /// Returns if some elements have been deleted since the last garbage collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let v = m.add_vertex();
+    /// assert!(!m.topology.has_garbage());
+    /// m.delete_vertex(v);
+    /// assert!(m.topology.has_garbage());
+    /// m.garbage_collection();
+    /// assert!(!m.topology.has_garbage());
+    /// ```
+    pub fn has_garbage(&self) -> bool {
+        self.garbage_
+    }
+
     /// Returns the `Face` incident to the `Halfedge`.
     ///
     /// # Examples
@@ -178,7 +383,7 @@ impl Topology {
     /// assert!(m.topology.face(h).unwrap() == f);
     /// ```
     pub fn face(&self, h : Halfedge) -> Option<Face> {
-        self.hconn_[h].face_
+        self.hconn_[h].face_.as_option()
     }
 
     /// Returns an outgoing `Haldedge` of `Vertex` `v`.
@@ -199,7 +404,7 @@ impl Topology {
     /// assert!(m.topology.from_vertex(h) == vvec[0]);
     /// ```
     pub fn halfedge(&self, v : Vertex) -> Option<Halfedge> {
-        self.vconn_[v].halfedge_
+        self.vconn_[v].halfedge_.as_option()
     }
 
     /// Returns an outgoing `Haldedge` of `Edge` `e`.
@@ -407,6 +612,28 @@ impl Topology {
         self.next_halfedge(self.opposite_halfedge(h))
     }
 
+    /// Returns the `Halfedge` that is rotated counter-clockwise around the start `Vertex` of h.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vvec);
+    /// let h0 = m.topology.find_halfedge(vvec[0],vvec[1]).unwrap();
+    /// let h1 = m.topology.find_halfedge(vvec[0],vvec[2]).unwrap();
+    /// assert!(m.topology.ccw_rotated_halfedge(h1) == h0);
+    /// ```
+    pub fn ccw_rotated_halfedge(&self, h : Halfedge) -> Halfedge {
+        self.opposite_halfedge(self.prev_halfedge(h))
+    }
+
     /// find the `Halfedge` from start to end.
     /// Retunrs the `Halfedge` that is rotated clockwise around the start `Vertex` of h.
     ///
@@ -442,105 +669,479 @@ impl Topology {
         None
     }
 
-    /// Sets the outgoing `Halfedge` of `Vertex` v to h.
-    fn set_halfedge(&mut self, v : Vertex, h : Halfedge) {
-        self.vconn_[v].halfedge_ = Some(h);
-    }
-
-    /// Sets the incident `Face` to `Halfedge` h to f.
-    fn set_face(&mut self, h : Halfedge, f : Face) {
-        self.hconn_[h].face_ = Some(f);
-    }
-
-    /// Sets the `Vertex` the `Halfedge` h points to to v.
-    fn set_vertex(&mut self, h : Halfedge, v : Vertex) {
-        self.hconn_[h].vertex_ = v;
-    }
-
-    /// Sets the next `Halfedge` of h within the face to nh
-    fn set_next_halfedge(&mut self, h : Halfedge, nh : Halfedge) {
-        self.hconn_[h].next_halfedge_ = nh;
-        self.hconn_[nh].prev_halfedge_ = h;
-    }
-
-    /// Makes sure that the outgoing `Halfedge` of `Vertex` v is boundary halfedge if v is a boundary vertex.
-    fn adjust_outgoing_halfedge(&mut self, v : Vertex) {
-        let mut h;
-        match self.halfedge(v) {
-            Some(x) => h=x,
-            None => return,
-        }
-        let hh = h;
-        loop {
-            if self.is_boundary_halfedge(h) {
-                self.set_halfedge(v,h);
-                return;
-            }
-            h = self.cw_rotated_halfedge(h);
-            if h == hh {break;}
-        }
-    }
-}
-
-pub struct Properties {
-    vprop_ : PropertyContainer<Vertex>,
-    hprop_ : PropertyContainer<Halfedge>,
-    eprop_ : PropertyContainer<Edge>,
-    fprop_ : PropertyContainer<Face>,
-}
-
-impl Properties {
-    pub fn new() -> Properties {
-        Properties {
-            vprop_ : PropertyContainer::new(),
-            hprop_ : PropertyContainer::new(),
-            eprop_ : PropertyContainer::new(),
-            fprop_ : PropertyContainer::new(),
-        }
-    }
-
-    /// Add a vertex property with default value. If a vertex property with this name already exists, return `None`.
+    /// Returns if the `Edge` e can be flipped.
+    ///
+    /// The edge must be an interior edge between two triangles, and the edge joining
+    /// the two opposite vertices must not already exist.
     ///
     /// # Examples
     ///
     /// ```
     /// use lwmesh::Mesh;
+    /// use lwmesh::*;
     ///
     /// let mut m = Mesh::new();
-    /// let pv = m.properties.add_vertex_property::<u32>("v:my_prop",17);
-    /// assert!(pv.is_some());
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..4 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]);
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]);
+    /// let e = m.topology.edge(m.topology.find_halfedge(vvec[1],vvec[2]).unwrap());
+    /// assert!(m.topology.is_flip_ok(e));
+    /// let e = m.topology.edge(m.topology.find_halfedge(vvec[0],vvec[1]).unwrap());
+    /// assert!(!m.topology.is_flip_ok(e));
     /// ```
-    pub fn add_vertex_property<D : 'static + Clone>(&mut self, name : & 'static str, default_value : D) -> Option<PropertyVertex<D>> {
-        self.vprop_.add::<D>(name,default_value)
+    pub fn is_flip_ok(&self, e : Edge) -> bool {
+        // deleted and boundary edges cannot be flipped
+        if self.is_deleted_edge(e) || self.is_boundary_edge(e) {
+            return false;
+        }
+
+        // only an edge between two triangles can be flipped
+        let h0 = self.edge_halfedge(e,0);
+        let h1 = self.edge_halfedge(e,1);
+        let n0 = self.next_halfedge(h0);
+        let n1 = self.next_halfedge(h1);
+        if self.next_halfedge(self.next_halfedge(n0)) != h0 || self.next_halfedge(self.next_halfedge(n1)) != h1 {
+            return false;
+        }
+
+        // check if the flipped edge is already present in the mesh
+        let v0 = self.to_vertex(n0);
+        let v1 = self.to_vertex(n1);
+        if v0 == v1 {
+            return false;
+        }
+        self.find_halfedge(v0,v1).is_none()
     }
 
-    /// Add a face property with default value. If a face property with this name already exists, return `None`.
+    /// Flips the `Edge` e, i.e. replaces it by the edge joining the two opposite vertices
+    /// of its incident triangles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `is_flip_ok(e)` is false.
     ///
     /// # Examples
     ///
     /// ```
     /// use lwmesh::Mesh;
+    /// use lwmesh::*;
     ///
     /// let mut m = Mesh::new();
-    /// let pf = m.properties.add_face_property::<u32>("f:my_prop",17);
-    /// assert!(pf.is_some());
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..4 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]);
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]);
+    /// let e = m.topology.edge(m.topology.find_halfedge(vvec[1],vvec[2]).unwrap());
+    /// m.topology.flip(e);
+    /// assert!(m.topology.find_halfedge(vvec[1],vvec[2]).is_none());
+    /// assert!(m.topology.find_halfedge(vvec[0],vvec[3]).is_some());
     /// ```
-    pub fn add_face_property<D : 'static + Clone>(&mut self, name : & 'static str, default_value : D) -> Option<PropertyFace<D>> {
-        self.fprop_.add::<D>(name,default_value)
+    pub fn flip(&mut self, e : Edge) {
+        assert!(self.is_flip_ok(e));
+
+        let a0 = self.edge_halfedge(e,0);
+        let b0 = self.edge_halfedge(e,1);
+
+        let a1 = self.next_halfedge(a0);
+        let a2 = self.next_halfedge(a1);
+
+        let b1 = self.next_halfedge(b0);
+        let b2 = self.next_halfedge(b1);
+
+        let va0 = self.to_vertex(a0);
+        let va1 = self.to_vertex(a1);
+
+        let vb0 = self.to_vertex(b0);
+        let vb1 = self.to_vertex(b1);
+
+        let fa = self.face(a0).unwrap();
+        let fb = self.face(b0).unwrap();
+
+        self.set_vertex(a0,va1);
+        self.set_vertex(b0,vb1);
+
+        self.set_next_halfedge(a0,a2);
+        self.set_next_halfedge(a2,b1);
+        self.set_next_halfedge(b1,a0);
+
+        self.set_next_halfedge(b0,b2);
+        self.set_next_halfedge(b2,a1);
+        self.set_next_halfedge(a1,b0);
+
+        self.set_face(a1,fb);
+        self.set_face(b1,fa);
+
+        self.set_face_halfedge(fa,a0);
+        self.set_face_halfedge(fb,b0);
+
+        if self.halfedge(va0) == Some(b0) {
+            self.set_halfedge(va0,a1);
+        }
+        if self.halfedge(vb0) == Some(a0) {
+            self.set_halfedge(vb0,b1);
+        }
     }
 
-    /// Add a edge property with default value. If a edge property with this name already exists, return `None`.
+    /// Returns if the `Halfedge` h can be collapsed.
+    ///
+    /// The collapse must keep the `Mesh` manifold: the one-rings of the two vertices of h must only
+    /// share the opposite vertices of its incident triangles (link condition).
     ///
     /// # Examples
     ///
     /// ```
     /// use lwmesh::Mesh;
+    /// use lwmesh::*;
     ///
     /// let mut m = Mesh::new();
-    /// let pe = m.properties.add_edge_property::<u32>("e:my_prop",17);
-    /// assert!(pe.is_some());
-    /// ```
-    pub fn add_edge_property<D : 'static + Clone>(&mut self, name : & 'static str, default_value : D) -> Option<PropertyEdge<D>> {
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..4 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]);
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]);
+    /// let h = m.topology.find_halfedge(vvec[0],vvec[1]).unwrap();
+    /// assert!(m.topology.is_collapse_ok(h));
+    /// let h = m.topology.find_halfedge(vvec[1],vvec[2]).unwrap();
+    /// assert!(!m.topology.is_collapse_ok(h));
+    /// ```
+    pub fn is_collapse_ok(&self, v0v1 : Halfedge) -> bool {
+        if self.is_deleted_halfedge(v0v1) {
+            return false;
+        }
+
+        let v1v0 = self.opposite_halfedge(v0v1);
+        let v0 = self.to_vertex(v1v0);
+        let v1 = self.to_vertex(v0v1);
+        let mut vl = None;
+        let mut vr = None;
+
+        // the edges v1-vl and vl-v0 must not be both boundary edges
+        if !self.is_boundary_halfedge(v0v1) {
+            let h1 = self.next_halfedge(v0v1);
+            let h2 = self.next_halfedge(h1);
+            vl = Some(self.to_vertex(h1));
+            if self.is_boundary_halfedge(self.opposite_halfedge(h1)) && self.is_boundary_halfedge(self.opposite_halfedge(h2)) {
+                return false;
+            }
+        }
+
+        // the edges v0-vr and vr-v1 must not be both boundary edges
+        if !self.is_boundary_halfedge(v1v0) {
+            let h1 = self.next_halfedge(v1v0);
+            let h2 = self.next_halfedge(h1);
+            vr = Some(self.to_vertex(h1));
+            if self.is_boundary_halfedge(self.opposite_halfedge(h1)) && self.is_boundary_halfedge(self.opposite_halfedge(h2)) {
+                return false;
+            }
+        }
+
+        // if vl and vr are equal or both invalid -> fail
+        if vl == vr {
+            return false;
+        }
+
+        // edge between two boundary vertices should be a boundary edge
+        if self.is_boundary_vertex(v0) && self.is_boundary_vertex(v1) &&
+            !self.is_boundary_halfedge(v0v1) && !self.is_boundary_halfedge(v1v0) {
+            return false;
+        }
+
+        // test intersection of the one-rings of v0 and v1
+        for vv in self.vertices_around(v0) {
+            if vv != v1 && Some(vv) != vl && Some(vv) != vr && self.find_halfedge(vv,v1).is_some() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Collapses the `Halfedge` h, i.e. merges its from `Vertex` into its to `Vertex`.
+    ///
+    /// The removed elements are only marked as deleted, call `Mesh::garbage_collection` to actually remove them.
+    /// Use `is_collapse_ok` to check that the collapse is legal before calling this function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..4 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]);
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]);
+    /// let h = m.topology.find_halfedge(vvec[0],vvec[1]).unwrap();
+    /// m.topology.collapse(h);
+    /// assert!(m.topology.is_deleted_vertex(vvec[0]));
+    /// assert_eq!(m.topology.n_faces(),1);
+    /// assert_eq!(m.topology.n_edges(),3);
+    /// ```
+    pub fn collapse(&mut self, h : Halfedge) {
+        let h0 = h;
+        let h1 = self.prev_halfedge(h0);
+        let o0 = self.opposite_halfedge(h0);
+        let o1 = self.next_halfedge(o0);
+
+        // remove edge
+        self.remove_edge(h0);
+
+        // remove loops
+        if self.next_halfedge(self.next_halfedge(h1)) == h1 {
+            self.remove_loop(h1);
+        }
+        if self.next_halfedge(self.next_halfedge(o1)) == o1 {
+            self.remove_loop(o1);
+        }
+    }
+
+    /// Removes the edge of `Halfedge` h by merging its from `Vertex` into its to `Vertex`.
+    fn remove_edge(&mut self, h : Halfedge) {
+        let hn = self.next_halfedge(h);
+        let hp = self.prev_halfedge(h);
+
+        let o = self.opposite_halfedge(h);
+        let on = self.next_halfedge(o);
+        let op = self.prev_halfedge(o);
+
+        let fh = self.face(h);
+        let fo = self.face(o);
+
+        let vh = self.to_vertex(h);
+        let vo = self.to_vertex(o);
+
+        // halfedge -> vertex
+        let hvec : Vec<Halfedge> = self.halfedges_around(vo).collect();
+        for hc in hvec {
+            let ohc = self.opposite_halfedge(hc);
+            self.set_vertex(ohc,vh);
+        }
+
+        // halfedge -> halfedge
+        self.set_next_halfedge(hp,hn);
+        self.set_next_halfedge(op,on);
+
+        // face -> halfedge
+        if let Some(f) = fh {
+            self.set_face_halfedge(f,hn);
+        }
+        if let Some(f) = fo {
+            self.set_face_halfedge(f,on);
+        }
+
+        // vertex -> halfedge
+        if self.halfedge(vh) == Some(o) {
+            self.set_halfedge(vh,hn);
+        }
+        self.adjust_outgoing_halfedge(vh);
+        self.vconn_[vo].halfedge_ = Halfedge::invalid();
+
+        // delete stuff
+        self.mark_deleted_vertex(vo);
+        let e = self.edge(h);
+        self.mark_deleted_edge(e);
+    }
+
+    /// Removes the two-edges loop starting at `Halfedge` h, created by a collapse.
+    fn remove_loop(&mut self, h : Halfedge) {
+        let h0 = h;
+        let h1 = self.next_halfedge(h0);
+
+        let o0 = self.opposite_halfedge(h0);
+        let o1 = self.opposite_halfedge(h1);
+
+        let v0 = self.to_vertex(h0);
+        let v1 = self.to_vertex(h1);
+
+        let fh = self.face(h0);
+        let fo = self.face(o0);
+
+        // is it a loop ?
+        assert!(self.next_halfedge(h1) == h0 && h1 != o0);
+
+        // halfedge -> halfedge
+        let on = self.next_halfedge(o0);
+        let op = self.prev_halfedge(o0);
+        self.set_next_halfedge(h1,on);
+        self.set_next_halfedge(op,h1);
+
+        // halfedge -> face
+        self.hconn_[h1].face_ = fo.unwrap_or(Face::invalid());
+
+        // vertex -> halfedge
+        self.set_halfedge(v0,h1);
+        self.adjust_outgoing_halfedge(v0);
+        self.set_halfedge(v1,o1);
+        self.adjust_outgoing_halfedge(v1);
+
+        // face -> halfedge
+        if let Some(f) = fo {
+            if self.face_halfedge(f) == o0 {
+                self.set_face_halfedge(f,h1);
+            }
+        }
+
+        // delete stuff
+        if let Some(f) = fh {
+            self.mark_deleted_face(f);
+        }
+        let e = self.edge(h0);
+        self.mark_deleted_edge(e);
+    }
+
+    /// Sets the outgoing `Halfedge` of `Vertex` v to h.
+    fn set_halfedge(&mut self, v : Vertex, h : Halfedge) {
+        self.vconn_[v].halfedge_ = h;
+    }
+
+    /// Sets the incident `Face` to `Halfedge` h to f.
+    fn set_face(&mut self, h : Halfedge, f : Face) {
+        self.hconn_[h].face_ = f;
+    }
+
+    /// Sets the `Halfedge` of `Face` f to h.
+    fn set_face_halfedge(&mut self, f : Face, h : Halfedge) {
+        self.fconn_[f].halfedge_ = h;
+    }
+
+    /// Sets the `Vertex` the `Halfedge` h points to to v.
+    fn set_vertex(&mut self, h : Halfedge, v : Vertex) {
+        self.hconn_[h].vertex_ = v;
+    }
+
+    /// Sets the next `Halfedge` of h within the face to nh
+    fn set_next_halfedge(&mut self, h : Halfedge, nh : Halfedge) {
+        self.hconn_[h].next_halfedge_ = nh;
+        self.hconn_[nh].prev_halfedge_ = h;
+    }
+
+    /// Marks the `Vertex` v as deleted.
+    fn mark_deleted_vertex(&mut self, v : Vertex) {
+        if !self.vdeleted_[v] {
+            self.vdeleted_[v] = true;
+            self.deleted_vertices_ += 1;
+            self.garbage_ = true;
+        }
+    }
+
+    /// Marks the `Edge` e as deleted.
+    fn mark_deleted_edge(&mut self, e : Edge) {
+        if !self.edeleted_[e] {
+            self.edeleted_[e] = true;
+            self.deleted_edges_ += 1;
+            self.garbage_ = true;
+        }
+    }
+
+    /// Marks the `Face` f as deleted.
+    fn mark_deleted_face(&mut self, f : Face) {
+        if !self.fdeleted_[f] {
+            self.fdeleted_[f] = true;
+            self.deleted_faces_ += 1;
+            self.garbage_ = true;
+        }
+    }
+
+    /// Makes sure that the outgoing `Halfedge` of `Vertex` v is boundary halfedge if v is a boundary vertex.
+    fn adjust_outgoing_halfedge(&mut self, v : Vertex) {
+        let mut h;
+        match self.halfedge(v) {
+            Some(x) => h=x,
+            None => return,
+        }
+        let hh = h;
+        loop {
+            if self.is_boundary_halfedge(h) {
+                self.set_halfedge(v,h);
+                return;
+            }
+            h = self.cw_rotated_halfedge(h);
+            if h == hh {break;}
+        }
+    }
+}
+
+/// The element types owning properties, to access their `PropertyContainer` generically.
+///
+/// It is implemented for the elements of the `Vertex`, `Halfedge`, `Edge` and `Face` handles,
+/// and appears in the bounds of the generic property accessors such as `Properties::try_get`.
+/// Its methods use the private `PropertyContainer`, so it cannot be implemented outside of this crate.
+pub trait PropertyElement : Sized + 'static {
+    #[doc(hidden)]
+    fn container(properties : &Properties) -> &PropertyContainer<Handle<Self>>;
+    #[doc(hidden)]
+    fn container_mut(properties : &mut Properties) -> &mut PropertyContainer<Handle<Self>>;
+}
+
+#[derive(Clone)]
+pub struct Properties {
+    vprop_ : PropertyContainer<Vertex>,
+    hprop_ : PropertyContainer<Halfedge>,
+    eprop_ : PropertyContainer<Edge>,
+    fprop_ : PropertyContainer<Face>,
+}
+
+impl Properties {
+    pub fn new() -> Properties {
+        Properties {
+            vprop_ : PropertyContainer::new(),
+            hprop_ : PropertyContainer::new(),
+            eprop_ : PropertyContainer::new(),
+            fprop_ : PropertyContainer::new(),
+        }
+    }
+
+    /// Add a vertex property with default value. If a vertex property with this name already exists, return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pv = m.properties.add_vertex_property::<u32>("v:my_prop",17);
+    /// assert!(pv.is_some());
+    /// ```
+    pub fn add_vertex_property<D : 'static + Clone>(&mut self, name : &str, default_value : D) -> Option<PropertyVertex<D>> {
+        self.vprop_.add::<D>(name,default_value)
+    }
+
+    /// Add a face property with default value. If a face property with this name already exists, return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pf = m.properties.add_face_property::<u32>("f:my_prop",17);
+    /// assert!(pf.is_some());
+    /// ```
+    pub fn add_face_property<D : 'static + Clone>(&mut self, name : &str, default_value : D) -> Option<PropertyFace<D>> {
+        self.fprop_.add::<D>(name,default_value)
+    }
+
+    /// Add a edge property with default value. If a edge property with this name already exists, return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pe = m.properties.add_edge_property::<u32>("e:my_prop",17);
+    /// assert!(pe.is_some());
+    /// ```
+    pub fn add_edge_property<D : 'static + Clone>(&mut self, name : &str, default_value : D) -> Option<PropertyEdge<D>> {
         self.eprop_.add::<D>(name,default_value)
     }
 
@@ -555,7 +1156,7 @@ impl Properties {
     /// let ph = m.properties.add_halfedge_property::<u32>("h:my_prop",17);
     /// assert!(ph.is_some());
     /// ```
-    pub fn add_halfedge_property<D : 'static + Clone>(&mut self, name : & 'static str, default_value : D) -> Option<PropertyHalfedge<D>> {
+    pub fn add_halfedge_property<D : 'static + Clone>(&mut self, name : &str, default_value : D) -> Option<PropertyHalfedge<D>> {
         self.hprop_.add::<D>(name,default_value)
     }
 
@@ -571,7 +1172,7 @@ impl Properties {
     /// let pv = m.properties.get_vertex_property::<u32>("v:my_prop");
     /// assert!(pv.is_some());
     /// ```
-    pub fn get_vertex_property<D : 'static + Clone>(&self, name : & 'static str) -> Option<PropertyVertex<D>> {
+    pub fn get_vertex_property<D : 'static + Clone>(&self, name : &str) -> Option<PropertyVertex<D>> {
         self.vprop_.get::<D>(name)
     }
 
@@ -587,7 +1188,7 @@ impl Properties {
     /// let pf = m.properties.get_face_property::<u32>("f:my_prop");
     /// assert!(pf.is_some());
     /// ```
-    pub fn get_face_property<D : 'static + Clone>(&self, name : & 'static str) -> Option<PropertyFace<D>> {
+    pub fn get_face_property<D : 'static + Clone>(&self, name : &str) -> Option<PropertyFace<D>> {
         self.fprop_.get::<D>(name)
     }
 
@@ -603,7 +1204,7 @@ impl Properties {
     /// let pe = m.properties.get_edge_property::<u32>("e:my_prop");
     /// assert!(pe.is_some());
     /// ```
-    pub fn get_edge_property<D : 'static + Clone>(&self, name : & 'static str) -> Option<PropertyEdge<D>> {
+    pub fn get_edge_property<D : 'static + Clone>(&self, name : &str) -> Option<PropertyEdge<D>> {
         self.eprop_.get::<D>(name)
     }
 
@@ -619,36 +1220,49 @@ impl Properties {
     /// let ph = m.properties.get_halfedge_property::<u32>("h:my_prop");
     /// assert!(ph.is_some());
     /// ```
-    pub fn get_halfedge_property<D : 'static + Clone>(&self, name : & 'static str) -> Option<PropertyHalfedge<D>> {
+    pub fn get_halfedge_property<D : 'static + Clone>(&self, name : &str) -> Option<PropertyHalfedge<D>> {
         self.hprop_.get::<D>(name)
     }
 
+    /// Remove the vertex property `p`. If it was already removed, return `false`.
+    ///
+    /// The handles of the other vertex properties remain valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pv = m.properties.add_vertex_property::<u32>("v:my_prop",17).unwrap();
+    /// assert!(m.properties.remove_vertex_property(pv));
+    /// assert!(m.properties.get_vertex_property::<u32>("v:my_prop").is_none());
+    /// ```
+    pub fn remove_vertex_property<D : 'static>(&mut self, p : PropertyVertex<D>) -> bool {
+        self.vprop_.remove::<D>(p)
+    }
 
-}
-
-pub struct Mesh {
-    pub topology : Topology,
-    pub properties : Properties
-}
-
-impl Mesh {
-    /// Constructs an empty `Mesh`.
+    /// Remove the face property `p`. If it was already removed, return `false`.
+    ///
+    /// The handles of the other face properties remain valid.
     ///
     /// # Examples
     ///
     /// ```
     /// use lwmesh::Mesh;
     ///
-    /// let m = Mesh::new();
+    /// let mut m = Mesh::new();
+    /// let pf = m.properties.add_face_property::<u32>("f:my_prop",17).unwrap();
+    /// assert!(m.properties.remove_face_property(pf));
+    /// assert!(m.properties.get_face_property::<u32>("f:my_prop").is_none());
     /// ```
-    pub fn new() -> Mesh {
-        Mesh {
-            topology : Topology::new(),
-            properties : Properties::new()
-        }
+    pub fn remove_face_property<D : 'static>(&mut self, p : PropertyFace<D>) -> bool {
+        self.fprop_.remove::<D>(p)
     }
 
-    /// Reserve the minimun capacity to store at least `size` vertex.
+    /// Remove the edge property `p`. If it was already removed, return `false`.
+    ///
+    /// The handles of the other edge properties remain valid.
     ///
     /// # Examples
     ///
@@ -656,15 +1270,17 @@ impl Mesh {
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::new();
-    /// m.vertex_reserve(15);
-    /// assert_eq!(m.vertex_capacity(),15);
+    /// let pe = m.properties.add_edge_property::<u32>("e:my_prop",17).unwrap();
+    /// assert!(m.properties.remove_edge_property(pe));
+    /// assert!(m.properties.get_edge_property::<u32>("e:my_prop").is_none());
     /// ```
-    pub fn vertex_reserve(&mut self, size : usize) {
-        self.topology.vconn_.reserve(size);
-        self.properties.vprop_.reserve(size);
+    pub fn remove_edge_property<D : 'static>(&mut self, p : PropertyEdge<D>) -> bool {
+        self.eprop_.remove::<D>(p)
     }
 
-    /// Returns the number of vertex the given `Mesh` can hold without reallocating.
+    /// Remove the halfedge property `p`. If it was already removed, return `false`.
+    ///
+    /// The handles of the other halfedge properties remain valid.
     ///
     /// # Examples
     ///
@@ -672,14 +1288,16 @@ impl Mesh {
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::new();
-    /// m.vertex_reserve(15);
-    /// assert_eq!(m.vertex_capacity(),15);
+    /// let ph = m.properties.add_halfedge_property::<u32>("h:my_prop",17).unwrap();
+    /// assert!(m.properties.remove_halfedge_property(ph));
+    /// assert!(m.properties.get_halfedge_property::<u32>("h:my_prop").is_none());
     /// ```
-    pub fn vertex_capacity(&self) -> usize {
-        self.topology.vconn_.capacity()
+    pub fn remove_halfedge_property<D : 'static>(&mut self, p : PropertyHalfedge<D>) -> bool {
+        self.hprop_.remove::<D>(p)
     }
 
-    /// Reserve the minimun capacity to store at least `size` face.
+    /// Rename the vertex property `old_name` into `new_name`.
+    /// If `old_name` does not exist or `new_name` already exists, return `false`.
     ///
     /// # Examples
     ///
@@ -687,15 +1305,16 @@ impl Mesh {
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::new();
-    /// m.face_reserve(17);
-    /// assert_eq!(m.face_capacity(),17);
+    /// m.properties.add_vertex_property::<u32>("v:my_prop",17);
+    /// assert!(m.properties.rename_vertex_property("v:my_prop","v:renamed"));
+    /// assert!(m.properties.get_vertex_property::<u32>("v:renamed").is_some());
     /// ```
-    pub fn face_reserve(&mut self, size : usize) {
-        self.topology.fconn_.reserve(size);
-        self.properties.fprop_.reserve(size);
+    pub fn rename_vertex_property(&mut self, old_name : &str, new_name : &str) -> bool {
+        self.vprop_.rename(old_name,new_name)
     }
 
-    /// Returns the number of face the given `Mesh` can hold without reallocating.
+    /// Rename the face property `old_name` into `new_name`.
+    /// If `old_name` does not exist or `new_name` already exists, return `false`.
     ///
     /// # Examples
     ///
@@ -703,14 +1322,16 @@ impl Mesh {
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::new();
-    /// m.face_reserve(17);
-    /// assert_eq!(m.face_capacity(),17);
+    /// m.properties.add_face_property::<u32>("f:my_prop",17);
+    /// assert!(m.properties.rename_face_property("f:my_prop","f:renamed"));
+    /// assert!(m.properties.get_face_property::<u32>("f:renamed").is_some());
     /// ```
-    pub fn face_capacity(&self) -> usize {
-        self.topology.fconn_.capacity()
+    pub fn rename_face_property(&mut self, old_name : &str, new_name : &str) -> bool {
+        self.fprop_.rename(old_name,new_name)
     }
 
-    /// Reserve the minimun capacity to store at least `size` edge.
+    /// Rename the edge property `old_name` into `new_name`.
+    /// If `old_name` does not exist or `new_name` already exists, return `false`.
     ///
     /// # Examples
     ///
@@ -718,16 +1339,16 @@ impl Mesh {
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::new();
-    /// m.edge_reserve(17);
-    /// assert_eq!(m.edge_capacity(),17);
+    /// m.properties.add_edge_property::<u32>("e:my_prop",17);
+    /// assert!(m.properties.rename_edge_property("e:my_prop","e:renamed"));
+    /// assert!(m.properties.get_edge_property::<u32>("e:renamed").is_some());
     /// ```
-    pub fn edge_reserve(&mut self, size : usize) {
-        self.topology.hconn_.reserve(size*2);
-        self.properties.eprop_.reserve(size);
-        self.properties.hprop_.reserve(size*2);
+    pub fn rename_edge_property(&mut self, old_name : &str, new_name : &str) -> bool {
+        self.eprop_.rename(old_name,new_name)
     }
 
-    /// Returns the number of edge the given `Mesh` can hold without reallocating.
+    /// Rename the halfedge property `old_name` into `new_name`.
+    /// If `old_name` does not exist or `new_name` already exists, return `false`.
     ///
     /// # Examples
     ///
@@ -735,14 +1356,15 @@ impl Mesh {
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::new();
-    /// m.edge_reserve(17);
-    /// assert_eq!(m.edge_capacity(),17);
+    /// m.properties.add_halfedge_property::<u32>("h:my_prop",17);
+    /// assert!(m.properties.rename_halfedge_property("h:my_prop","h:renamed"));
+    /// assert!(m.properties.get_halfedge_property::<u32>("h:renamed").is_some());
     /// ```
-    pub fn edge_capacity(&self) -> usize {
-        self.topology.hconn_.capacity()/2
+    pub fn rename_halfedge_property(&mut self, old_name : &str, new_name : &str) -> bool {
+        self.hprop_.rename(old_name,new_name)
     }
 
-    /// Adds a new vertex to the `Mesh`
+    /// Returns the names of all vertex properties.
     ///
     /// # Examples
     ///
@@ -750,15 +1372,14 @@ impl Mesh {
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::new();
-    /// let v = m.add_vertex();
+    /// m.properties.add_vertex_property::<u32>("v:my_prop",17);
+    /// assert_eq!(m.properties.vertex_property_names(),vec!["v:my_prop"]);
     /// ```
-    pub fn add_vertex(&mut self) -> Vertex {
-        self.properties.vprop_.push();
-        self.topology.vconn_.push();
-        Vertex::new(self.topology.vconn_.len()-1)
+    pub fn vertex_property_names(&self) -> Vec<&str> {
+        self.vprop_.names()
     }
 
-    /// Adds new vertices to the `Mesh`
+    /// Returns the names of all face properties.
     ///
     /// # Examples
     ///
@@ -766,25 +1387,1125 @@ impl Mesh {
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::new();
-    /// let vec = m.add_vertices(17);
-    /// assert_eq!(m.topology.n_vertices(),17);
-    /// assert_eq!(vec.len(),17);
+    /// m.properties.add_face_property::<u32>("f:my_prop",17);
+    /// assert_eq!(m.properties.face_property_names(),vec!["f:my_prop"]);
     /// ```
-    pub fn add_vertices(&mut self, nb : usize) -> Vec<Vertex> {
-        let mut vec : Vec<Vertex> = Vec::new();
-        if self.vertex_capacity() < self.topology.n_vertices()+nb {
+    pub fn face_property_names(&self) -> Vec<&str> {
+        self.fprop_.names()
+    }
+
+    /// Returns the names of all edge properties.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.properties.add_edge_property::<u32>("e:my_prop",17);
+    /// assert_eq!(m.properties.edge_property_names(),vec!["e:my_prop"]);
+    /// ```
+    pub fn edge_property_names(&self) -> Vec<&str> {
+        self.eprop_.names()
+    }
+
+    /// Returns the names of all halfedge properties.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.properties.add_halfedge_property::<u32>("h:my_prop",17);
+    /// assert_eq!(m.properties.halfedge_property_names(),vec!["h:my_prop"]);
+    /// ```
+    pub fn halfedge_property_names(&self) -> Vec<&str> {
+        self.hprop_.names()
+    }
+
+    /// Copies the values of all vertex properties of `from` to `to`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let prop = m.properties.add_vertex_property::<u32>("v:my_prop",17).unwrap();
+    /// let v0 = m.add_vertex();
+    /// let v1 = m.add_vertex();
+    /// m.properties[(prop,v0)] = 42;
+    /// m.properties.copy_vertex_properties(v0,v1);
+    /// assert_eq!(m.properties[(prop,v1)],42);
+    /// ```
+    pub fn copy_vertex_properties(&mut self, from : Vertex, to : Vertex) {
+        self.vprop_.copy(from.idx(),to.idx());
+    }
+
+    /// Copies the values of all face properties of `from` to `to`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let prop = m.properties.add_face_property::<u32>("f:my_prop",17).unwrap();
+    /// let vvec = m.add_vertices(4);
+    /// let f0 = m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+    /// let f1 = m.add_face(&vec![vvec[2],vvec[1],vvec[3]]).unwrap();
+    /// m.properties[(prop,f0)] = 42;
+    /// m.properties.copy_face_properties(f0,f1);
+    /// assert_eq!(m.properties[(prop,f1)],42);
+    /// ```
+    pub fn copy_face_properties(&mut self, from : Face, to : Face) {
+        self.fprop_.copy(from.idx(),to.idx());
+    }
+
+    /// Copies the values of all edge properties of `from` to `to`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let prop = m.properties.add_edge_property::<u32>("e:my_prop",17).unwrap();
+    /// let vvec = m.add_vertices(3);
+    /// m.add_face(&vvec).unwrap();
+    /// m.properties[(prop,Edge::new(0))] = 42;
+    /// m.properties.copy_edge_properties(Edge::new(0),Edge::new(1));
+    /// assert_eq!(m.properties[(prop,Edge::new(1))],42);
+    /// ```
+    pub fn copy_edge_properties(&mut self, from : Edge, to : Edge) {
+        self.eprop_.copy(from.idx(),to.idx());
+    }
+
+    /// Copies the values of all halfedge properties of `from` to `to`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let prop = m.properties.add_halfedge_property::<u32>("h:my_prop",17).unwrap();
+    /// let vvec = m.add_vertices(3);
+    /// m.add_face(&vvec).unwrap();
+    /// m.properties[(prop,Halfedge::new(0))] = 42;
+    /// m.properties.copy_halfedge_properties(Halfedge::new(0),Halfedge::new(1));
+    /// assert_eq!(m.properties[(prop,Halfedge::new(1))],42);
+    /// ```
+    pub fn copy_halfedge_properties(&mut self, from : Halfedge, to : Halfedge) {
+        self.hprop_.copy(from.idx(),to.idx());
+    }
+
+    /// Sets all the values of the halfedge property `name` to its default value.
+    /// If it does not exist, return `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let prop = m.properties.add_halfedge_property::<u32>("h:my_prop",17).unwrap();
+    /// let vvec = m.add_vertices(3);
+    /// m.add_face(&vvec).unwrap();
+    /// m.properties[(prop,Halfedge::new(0))] = 42;
+    /// assert!(m.properties.reset_halfedge_property("h:my_prop"));
+    /// assert_eq!(m.properties[(prop,Halfedge::new(0))],17);
+    /// ```
+    pub fn reset_halfedge_property(&mut self, name : &str) -> bool {
+        self.hprop_.reset(name)
+    }
+
+    /// Returns the name of the type stored in the vertex property `name`. If it does not exist, return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.properties.add_vertex_property::<u32>("v:my_prop",17);
+    /// assert_eq!(m.properties.vertex_property_type("v:my_prop"),Some("u32"));
+    /// ```
+    pub fn vertex_property_type(&self, name : &str) -> Option<& 'static str> {
+        self.vprop_.type_name(name)
+    }
+
+    /// Returns the name of the type stored in the face property `name`. If it does not exist, return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.properties.add_face_property::<u32>("f:my_prop",17);
+    /// assert_eq!(m.properties.face_property_type("f:my_prop"),Some("u32"));
+    /// ```
+    pub fn face_property_type(&self, name : &str) -> Option<& 'static str> {
+        self.fprop_.type_name(name)
+    }
+
+    /// Returns the name of the type stored in the edge property `name`. If it does not exist, return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.properties.add_edge_property::<u32>("e:my_prop",17);
+    /// assert_eq!(m.properties.edge_property_type("e:my_prop"),Some("u32"));
+    /// ```
+    pub fn edge_property_type(&self, name : &str) -> Option<& 'static str> {
+        self.eprop_.type_name(name)
+    }
+
+    /// Returns the name of the type stored in the halfedge property `name`. If it does not exist, return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.properties.add_halfedge_property::<u32>("h:my_prop",17);
+    /// assert_eq!(m.properties.halfedge_property_type("h:my_prop"),Some("u32"));
+    /// ```
+    pub fn halfedge_property_type(&self, name : &str) -> Option<& 'static str> {
+        self.hprop_.type_name(name)
+    }
+
+    /// Returns a copy of the given `Properties` keeping only the properties whose name is in `names`.
+    ///
+    /// The handles of the kept properties remain valid on the copy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.properties.add_vertex_property::<u32>("v:my_prop",17);
+    /// m.properties.add_face_property::<u32>("f:my_prop",17);
+    /// let copy = m.properties.clone_subset(&["f:my_prop"]);
+    /// assert_eq!(copy.property_names(),vec!["f:my_prop"]);
+    /// ```
+    pub fn clone_subset(&self, names : &[&str]) -> Properties {
+        Properties {
+            vprop_ : self.vprop_.clone_subset(names),
+            hprop_ : self.hprop_.clone_subset(names),
+            eprop_ : self.eprop_.clone_subset(names),
+            fprop_ : self.fprop_.clone_subset(names),
+        }
+    }
+
+    /// Returns the elements of the property `p` as a slice indexed by the handle indices,
+    /// including the elements of the deleted handles.
+    ///
+    /// Unlike `Properties`, the slice can be shared between threads when `D` is `Sync`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let prop = m.properties.add_vertex_property::<u32>("v:my_prop",17).unwrap();
+    /// let v0 = m.add_vertex();
+    /// m.add_vertex();
+    /// assert_eq!(m.properties.property_slice(prop)[v0.idx()],17);
+    /// assert_eq!(m.properties.property_slice(prop).len(),2);
+    /// ```
+    pub fn property_slice<T : PropertyElement, D : 'static>(&self, p : PropertyHandle<(T,D)>) -> &[D] {
+        match T::container(self).slice(p) {
+            Ok(x) => x,
+            Err(e) => panic!("invalid property access: {:?}",e),
+        }
+    }
+
+    /// Access the element `h` of the property `p`, or return why the access is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let prop = m.properties.add_vertex_property::<u32>("v:my_prop",17).unwrap();
+    /// let v0 = m.add_vertex();
+    /// assert_eq!(m.properties.try_get(prop,v0),Ok(&17));
+    /// assert_eq!(m.properties.try_get(prop,Vertex::new(1)),Err(PropertyError::OutOfBounds(1)));
+    /// ```
+    pub fn try_get<T : PropertyElement, D : 'static>(&self, p : PropertyHandle<(T,D)>, h : Handle<T>) -> Result<&D,PropertyError> {
+        T::container(self).try_get(p,h)
+    }
+
+    /// Mutable access to the element `h` of the property `p`, or return why the access is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut other = Mesh::new();
+    /// let prop = m.properties.add_vertex_property::<u32>("v:my_prop",17).unwrap();
+    /// other.properties.add_vertex_property::<u32>("v:my_prop",17).unwrap();
+    /// let v0 = m.add_vertex();
+    /// other.add_vertex();
+    /// *m.properties.try_get_mut(prop,v0).unwrap() = 42;
+    /// assert_eq!(m.properties[(prop,v0)],42);
+    /// assert_eq!(other.properties.try_get_mut(prop,v0),Err(PropertyError::WrongContainer));
+    /// ```
+    pub fn try_get_mut<T : PropertyElement, D : 'static>(&mut self, p : PropertyHandle<(T,D)>, h : Handle<T>) -> Result<&mut D,PropertyError> {
+        T::container_mut(self).try_get_mut(p,h)
+    }
+
+    /// Returns the names of all properties, vertex properties first, then halfedge, edge and face properties.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.properties.add_face_property::<u32>("f:my_prop",17);
+    /// m.properties.add_vertex_property::<u32>("v:my_prop",17);
+    /// assert_eq!(m.properties.property_names(),vec!["v:my_prop","f:my_prop"]);
+    /// ```
+    pub fn property_names(&self) -> Vec<&str> {
+        let mut names = self.vprop_.names();
+        names.extend(self.hprop_.names());
+        names.extend(self.eprop_.names());
+        names.extend(self.fprop_.names());
+        names
+    }
+
+}
+
+impl PropertyElement for PhantomVertex {
+    fn container(properties : &Properties) -> &PropertyContainer<Handle<PhantomVertex>> {
+        &properties.vprop_
+    }
+
+    fn container_mut(properties : &mut Properties) -> &mut PropertyContainer<Handle<PhantomVertex>> {
+        &mut properties.vprop_
+    }
+}
+
+impl PropertyElement for PhantomHalfedge {
+    fn container(properties : &Properties) -> &PropertyContainer<Handle<PhantomHalfedge>> {
+        &properties.hprop_
+    }
+
+    fn container_mut(properties : &mut Properties) -> &mut PropertyContainer<Handle<PhantomHalfedge>> {
+        &mut properties.hprop_
+    }
+}
+
+impl PropertyElement for PhantomEdge {
+    fn container(properties : &Properties) -> &PropertyContainer<Handle<PhantomEdge>> {
+        &properties.eprop_
+    }
+
+    fn container_mut(properties : &mut Properties) -> &mut PropertyContainer<Handle<PhantomEdge>> {
+        &mut properties.eprop_
+    }
+}
+
+impl PropertyElement for PhantomFace {
+    fn container(properties : &Properties) -> &PropertyContainer<Handle<PhantomFace>> {
+        &properties.fprop_
+    }
+
+    fn container_mut(properties : &mut Properties) -> &mut PropertyContainer<Handle<PhantomFace>> {
+        &mut properties.fprop_
+    }
+}
+
+#[derive(Clone)]
+pub struct Mesh {
+    pub topology : Topology,
+    pub properties : Properties
+}
+
+impl Mesh {
+    /// Constructs an empty `Mesh`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let m = Mesh::new();
+    /// ```
+    pub fn new() -> Mesh {
+        Mesh {
+            topology : Topology::new(),
+            properties : Properties::new()
+        }
+    }
+
+    /// Returns a copy of the `Mesh` with the same `Topology`, keeping only the properties whose name is in `names`.
+    ///
+    /// The handles of the kept properties remain valid on the copy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.add_positions::<[f32;3]>().unwrap();
+    /// m.properties.add_face_property::<u32>("f:my_prop",17).unwrap();
+    /// let vvec = m.add_vertices(3);
+    /// m.add_face(&vvec).unwrap();
+    /// let copy = m.copy_with_properties(&["v:position"]);
+    /// assert_eq!(copy.topology.n_faces(),1);
+    /// assert_eq!(copy.properties.property_names(),vec!["v:position"]);
+    /// ```
+    pub fn copy_with_properties(&self, names : &[&str]) -> Mesh {
+        Mesh {
+            topology : self.topology.clone(),
+            properties : self.properties.clone_subset(names),
+        }
+    }
+
+    /// Reserve the minimun capacity to store at least `size` vertex.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.vertex_reserve(15);
+    /// assert_eq!(m.vertex_capacity(),15);
+    /// ```
+    pub fn vertex_reserve(&mut self, size : usize) {
+        self.topology.vconn_.reserve(size);
+        self.topology.vdeleted_.reserve(size);
+        self.properties.vprop_.reserve(size);
+    }
+
+    /// Returns the number of vertex the given `Mesh` can hold without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.vertex_reserve(15);
+    /// assert_eq!(m.vertex_capacity(),15);
+    /// ```
+    pub fn vertex_capacity(&self) -> usize {
+        self.topology.vconn_.capacity()
+    }
+
+    /// Reserve the minimun capacity to store at least `size` face.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.face_reserve(17);
+    /// assert_eq!(m.face_capacity(),17);
+    /// ```
+    pub fn face_reserve(&mut self, size : usize) {
+        self.topology.fconn_.reserve(size);
+        self.topology.fdeleted_.reserve(size);
+        self.properties.fprop_.reserve(size);
+    }
+
+    /// Returns the number of face the given `Mesh` can hold without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.face_reserve(17);
+    /// assert_eq!(m.face_capacity(),17);
+    /// ```
+    pub fn face_capacity(&self) -> usize {
+        self.topology.fconn_.capacity()
+    }
+
+    /// Reserve the minimun capacity to store at least `size` edge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.edge_reserve(17);
+    /// assert_eq!(m.edge_capacity(),17);
+    /// ```
+    pub fn edge_reserve(&mut self, size : usize) {
+        self.topology.hconn_.reserve(size*2);
+        self.topology.edeleted_.reserve(size);
+        self.properties.eprop_.reserve(size);
+        self.properties.hprop_.reserve(size*2);
+    }
+
+    /// Returns the number of edge the given `Mesh` can hold without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// m.edge_reserve(17);
+    /// assert_eq!(m.edge_capacity(),17);
+    /// ```
+    pub fn edge_capacity(&self) -> usize {
+        self.topology.hconn_.capacity()/2
+    }
+
+    /// Adds a new vertex to the `Mesh`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let v = m.add_vertex();
+    /// ```
+    pub fn add_vertex(&mut self) -> Vertex {
+        self.properties.vprop_.push();
+        self.topology.vconn_.push();
+        self.topology.vdeleted_.push();
+        Vertex::new(self.topology.vconn_.len()-1)
+    }
+
+    /// Adds new vertices to the `Mesh`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vec = m.add_vertices(17);
+    /// assert_eq!(m.topology.n_vertices(),17);
+    /// assert_eq!(vec.len(),17);
+    /// ```
+    pub fn add_vertices(&mut self, nb : usize) -> Vec<Vertex> {
+        let mut vec : Vec<Vertex> = Vec::new();
+        if self.vertex_capacity() < self.topology.n_vertices()+nb {
             let new_cap = self.topology.n_vertices()+nb;
             self.vertex_reserve(new_cap);
         }
-        for _ in 0..nb {
-            self.properties.vprop_.push();
-            self.topology.vconn_.push();
-            vec.push(Vertex::new(self.topology.vconn_.len()-1));
+        for _ in 0..nb {
+            self.properties.vprop_.push();
+            self.topology.vconn_.push();
+            self.topology.vdeleted_.push();
+            vec.push(Vertex::new(self.topology.vconn_.len()-1));
+        }
+        return vec;
+    }
+
+    /// Adds a new `Face` to the `Mesh`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vvec);
+    /// # assert!(f.is_some());
+    /// ```
+    pub fn add_face(&mut self, vertices : & Vec<Vertex>) -> Option<Face> {
+        let n = vertices.len();
+        let mut hvec = Vec::<Option<Halfedge>>::new();
+        hvec.reserve(n);
+        let mut new_hvec = Vec::<bool>::new();
+        new_hvec.reserve(n);
+        let mut next_cache : Vec<(Halfedge,Halfedge)> = Vec::new();
+
+        // Does the face to add is valid
+        for i in 0..n {
+            if !self.topology.is_boundary_vertex(vertices[i]) {
+                return None;
+            }
+            hvec.push(self.topology.find_halfedge(vertices[i],vertices[(i+1)%n]));
+            new_hvec.push(hvec[i].is_none());
+            if !new_hvec[i] && !self.topology.is_boundary_halfedge(hvec[i].unwrap()) {
+                return None;
+            }
+        }
+
+        // re-link patches if necessary
+        for i in 0..n {
+            let ii = (i+1)%n;
+            if !new_hvec[i] && !new_hvec[ii] {
+                let inner_prev = hvec[i].unwrap();
+                let inner_next = hvec[ii].unwrap();
+                if self.topology.next_halfedge(inner_prev) != inner_next {
+                    let outer_prev = self.topology.opposite_halfedge(inner_next);
+                    let mut boundary_prev = outer_prev;
+                    loop {
+                        boundary_prev = self.topology.opposite_halfedge(self.topology.next_halfedge(boundary_prev));;
+                        if (self.topology.is_boundary_halfedge(boundary_prev)) && boundary_prev!=inner_prev {
+                            break;
+                        }
+                    }
+                    let boundary_next = self.topology.next_halfedge(boundary_prev);
+                    assert!(self.topology.is_boundary_halfedge(boundary_prev));
+                    assert!(self.topology.is_boundary_halfedge(boundary_next));
+                    if boundary_next == inner_next {
+                        return None;
+                    }
+                    let patch_start = self.topology.next_halfedge(inner_prev);
+                    let patch_end   = self.topology.prev_halfedge(inner_next);
+                    next_cache.push((boundary_prev, patch_start));
+                    next_cache.push((patch_end, boundary_next));
+                    next_cache.push((inner_prev, inner_next));
+                }
+            }
+        }
+
+
+        // Create missing edges
+        for i in 0..n {
+            if new_hvec[i] {
+                let ii = (i+1)%n;
+                hvec[i] = Some(self.new_edge(vertices[i], vertices[ii]));
+            }
+        }
+
+        // Creates the new face
+        let f = self.new_face();
+        self.topology.set_face_halfedge(f,hvec[n-1].unwrap());
+
+        // Setup halfedges
+        let mut needs_adjust : Vec<bool> = Vec::new();
+        needs_adjust.resize(n,false);
+        for i in 0..n {
+            let ii = (i+1)%n;
+            let v = vertices[ii];
+            let inner_prev = hvec[i].unwrap();
+            let inner_next = hvec[ii].unwrap();
+
+            if new_hvec[i] || new_hvec[ii] {
+                let outer_prev = self.topology.opposite_halfedge(inner_next);
+                let outer_next = self.topology.opposite_halfedge(inner_prev);
+
+                if !new_hvec[ii] { // prev is new, next is not
+                    let boundary_prev = self.topology.prev_halfedge(inner_next);
+                    next_cache.push((boundary_prev,outer_next));
+                    self.topology.set_halfedge(v,outer_next);
+                } else if !new_hvec[i] { // next is new, prev is not
+                    let boundary_next = self.topology.next_halfedge(inner_prev);
+                    next_cache.push((outer_prev,boundary_next));
+                    self.topology.set_halfedge(v,boundary_next);
+                } else { // both are new
+                    match self.topology.halfedge(v) {
+                        None => {
+                            self.topology.set_halfedge(v,outer_next);
+                            next_cache.push((outer_prev,outer_next));
+                        }
+                        Some(h) => {
+                            let boundary_prev = self.topology.prev_halfedge(h);
+                            next_cache.push((boundary_prev,outer_next));
+                            next_cache.push((outer_prev,h));
+                        }
+                    }
+                }
+                next_cache.push((inner_prev,inner_next));
+            } else {
+                needs_adjust[ii] = self.topology.halfedge(v).unwrap() == inner_next;
+            }
+            self.topology.set_face(inner_prev,f);
+        }
+
+        // process cache
+        for (first,second) in next_cache {
+            self.topology.set_next_halfedge(first,second);
+        }
+
+        // adjust vertices halfedge handle
+        for i in 0..n {
+            if needs_adjust[i] {
+                self.topology.adjust_outgoing_halfedge(vertices[i]);
+            }
+        }
+
+        return Some(f);
+    }
+
+    /// allocate a new face
+    fn new_face(&mut self) -> Face {
+        self.properties.fprop_.push();
+        self.topology.fconn_.push();
+        self.topology.fdeleted_.push();
+        Face::new(self.topology.fconn_.len()-1)
+    }
+
+    /// allocate a new edge and returns the `Halfedge` from start to end
+    fn new_edge(&mut self, start : Vertex, end : Vertex) -> Halfedge {
+        assert!(start != end);
+
+        self.properties.eprop_.push();
+        self.properties.hprop_.push();
+        self.properties.hprop_.push();
+        self.topology.edeleted_.push();
+        self.topology.hconn_.push();
+        let h0 = Halfedge::new(self.topology.hconn_.len()-1);
+        self.topology.hconn_.push();
+        let h1 = Halfedge::new(self.topology.hconn_.len()-1);
+
+        self.topology.set_vertex(h0, end);
+        self.topology.set_vertex(h1, start);
+
+        return h0;
+    }
+
+    /// Splits the `Edge` e by inserting the isolated `Vertex` v in its middle.
+    ///
+    /// The incident faces of e must be triangles, each of them is split in two triangles.
+    /// Returns the `Halfedge` pointing to v on the new edge.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if v is not isolated or if an incident face of e is not a triangle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..4 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]);
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]);
+    /// let e = m.topology.edge(m.topology.find_halfedge(vvec[1],vvec[2]).unwrap());
+    /// let v = m.add_vertex();
+    /// let h = m.split_edge(e,v);
+    /// assert!(m.topology.to_vertex(h) == v);
+    /// assert_eq!(m.topology.n_faces(),4);
+    /// assert_eq!(m.topology.n_edges(),8);
+    /// ```
+    pub fn split_edge(&mut self, e : Edge, v : Vertex) -> Halfedge {
+        let h0 = self.topology.edge_halfedge(e,0);
+        let o0 = self.topology.edge_halfedge(e,1);
+        debug_assert!(self.topology.halfedge(v).is_none());
+        debug_assert!([h0,o0].iter().all(|&h| self.topology.is_boundary_halfedge(h) || self.topology.halfedges_around(self.topology.face(h).unwrap()).count() == 3));
+
+        let v2 = self.topology.to_vertex(o0);
+
+        let e1 = self.new_edge(v,v2);
+        let t1 = self.topology.opposite_halfedge(e1);
+
+        let f0 = self.topology.face(h0);
+        let f3 = self.topology.face(o0);
+
+        self.topology.set_halfedge(v,h0);
+        self.topology.set_vertex(o0,v);
+
+        match f0 {
+            Some(f0) => {
+                let h1 = self.topology.next_halfedge(h0);
+                let h2 = self.topology.next_halfedge(h1);
+
+                let v1 = self.topology.to_vertex(h1);
+
+                let e0 = self.new_edge(v,v1);
+                let t0 = self.topology.opposite_halfedge(e0);
+
+                let f1 = self.new_face();
+                self.topology.set_face_halfedge(f0,h0);
+                self.topology.set_face_halfedge(f1,h2);
+
+                self.topology.set_face(h1,f0);
+                self.topology.set_face(t0,f0);
+                self.topology.set_face(h0,f0);
+
+                self.topology.set_face(h2,f1);
+                self.topology.set_face(t1,f1);
+                self.topology.set_face(e0,f1);
+
+                self.topology.set_next_halfedge(h0,h1);
+                self.topology.set_next_halfedge(h1,t0);
+                self.topology.set_next_halfedge(t0,h0);
+
+                self.topology.set_next_halfedge(e0,h2);
+                self.topology.set_next_halfedge(h2,t1);
+                self.topology.set_next_halfedge(t1,e0);
+            },
+            None => {
+                let prev = self.topology.prev_halfedge(h0);
+                self.topology.set_next_halfedge(prev,t1);
+                self.topology.set_next_halfedge(t1,h0);
+                // the outgoing halfedge of v already is h0
+            },
+        }
+
+        match f3 {
+            Some(f3) => {
+                let o1 = self.topology.next_halfedge(o0);
+                let o2 = self.topology.next_halfedge(o1);
+
+                let v3 = self.topology.to_vertex(o1);
+
+                let e2 = self.new_edge(v,v3);
+                let t2 = self.topology.opposite_halfedge(e2);
+
+                let f2 = self.new_face();
+                self.topology.set_face_halfedge(f2,o1);
+                self.topology.set_face_halfedge(f3,o0);
+
+                self.topology.set_face(o1,f2);
+                self.topology.set_face(t2,f2);
+                self.topology.set_face(e1,f2);
+
+                self.topology.set_face(o2,f3);
+                self.topology.set_face(o0,f3);
+                self.topology.set_face(e2,f3);
+
+                self.topology.set_next_halfedge(e1,o1);
+                self.topology.set_next_halfedge(o1,t2);
+                self.topology.set_next_halfedge(t2,e1);
+
+                self.topology.set_next_halfedge(o0,e2);
+                self.topology.set_next_halfedge(e2,o2);
+                self.topology.set_next_halfedge(o2,o0);
+            },
+            None => {
+                let next = self.topology.next_halfedge(o0);
+                self.topology.set_next_halfedge(e1,next);
+                self.topology.set_next_halfedge(o0,e1);
+                self.topology.set_halfedge(v,e1);
+            },
+        }
+
+        if self.topology.halfedge(v2) == Some(h0) {
+            self.topology.set_halfedge(v2,t1);
+        }
+
+        return t1;
+    }
+
+    /// Splits the `Face` f by connecting each of its vertices to the isolated `Vertex` v.
+    ///
+    /// The face is replaced by a fan of triangles around v.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if v is not isolated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..4 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vvec).unwrap();
+    /// let v = m.add_vertex();
+    /// m.split_face(f,v);
+    /// assert_eq!(m.topology.n_faces(),4);
+    /// assert_eq!(m.topology.n_edges(),8);
+    /// ```
+    pub fn split_face(&mut self, f : Face, v : Vertex) {
+        debug_assert!(self.topology.halfedge(v).is_none());
+        let hend = self.topology.face_halfedge(f);
+        let mut h = self.topology.next_halfedge(hend);
+
+        let to = self.topology.to_vertex(hend);
+        let mut hold = self.new_edge(to,v);
+
+        self.topology.set_next_halfedge(hend,hold);
+        self.topology.set_face(hold,f);
+
+        hold = self.topology.opposite_halfedge(hold);
+
+        while h != hend {
+            let hnext = self.topology.next_halfedge(h);
+
+            let fnew = self.new_face();
+            self.topology.set_face_halfedge(fnew,h);
+
+            let to = self.topology.to_vertex(h);
+            let hnew = self.new_edge(to,v);
+
+            self.topology.set_next_halfedge(hnew,hold);
+            self.topology.set_next_halfedge(hold,h);
+            self.topology.set_next_halfedge(h,hnew);
+
+            self.topology.set_face(hnew,fnew);
+            self.topology.set_face(hold,fnew);
+            self.topology.set_face(h,fnew);
+
+            hold = self.topology.opposite_halfedge(hnew);
+
+            h = hnext;
+        }
+
+        let next = self.topology.next_halfedge(hend);
+        self.topology.set_next_halfedge(hold,hend);
+        self.topology.set_next_halfedge(next,hold);
+        self.topology.set_face(hold,f);
+
+        self.topology.set_halfedge(v,hold);
+    }
+
+    /// Splits the face of the halfedges h0 and h1 by inserting an edge from the end of h0 to the end of h1.
+    ///
+    /// The face keeps h0 and the new edge, the other part of the face becomes a new face.
+    /// Returns the new `Halfedge` from the end of h0 to the end of h1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if h0 and h1 are not halfedges of the same face.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// let f = m.add_face(&vvec).unwrap();
+    /// let h0 = m.topology.find_halfedge(vvec[1],vvec[2]).unwrap();
+    /// let h1 = m.topology.find_halfedge(vvec[3],vvec[0]).unwrap();
+    /// let h = m.insert_edge(h0,h1);
+    /// assert!(m.topology.from_vertex(h) == vvec[2] && m.topology.to_vertex(h) == vvec[0]);
+    /// assert!(m.topology.face(h) == Some(f));
+    /// assert_eq!(m.topology.n_faces(),2);
+    /// ```
+    pub fn insert_edge(&mut self, h0 : Halfedge, h1 : Halfedge) -> Halfedge {
+        let f0 = self.topology.face(h0);
+        assert!(f0.is_some() && f0 == self.topology.face(h1));
+        let f0 = f0.unwrap();
+
+        let v0 = self.topology.to_vertex(h0);
+        let v1 = self.topology.to_vertex(h1);
+
+        let h2 = self.topology.next_halfedge(h0);
+        let h3 = self.topology.next_halfedge(h1);
+
+        let h4 = self.new_edge(v0,v1);
+        let h5 = self.topology.opposite_halfedge(h4);
+
+        let f1 = self.new_face();
+
+        self.topology.set_face_halfedge(f0,h0);
+        self.topology.set_face_halfedge(f1,h1);
+
+        self.topology.set_next_halfedge(h0,h4);
+        self.topology.set_next_halfedge(h4,h3);
+        self.topology.set_face(h4,f0);
+
+        self.topology.set_next_halfedge(h1,h5);
+        self.topology.set_next_halfedge(h5,h2);
+        let mut h = h2;
+        loop {
+            self.topology.set_face(h,f1);
+            h = self.topology.next_halfedge(h);
+            if h == h2 {
+                break;
+            }
+        }
+
+        return h4;
+    }
+
+    /// Deletes the `Vertex` v and all its incident faces from the `Mesh`.
+    ///
+    /// The vertex is only marked as deleted, call `garbage_collection` to actually remove it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..4 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]);
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]);
+    /// m.delete_vertex(vvec[0]);
+    /// assert_eq!(m.topology.n_vertices(),3);
+    /// assert_eq!(m.topology.n_faces(),1);
+    /// ```
+    pub fn delete_vertex(&mut self, v : Vertex) {
+        if self.topology.is_deleted_vertex(v) {
+            return;
+        }
+
+        // collect incident faces
+        let fvec : Vec<Face> = self.topology.faces_around(v).collect();
+
+        // delete them
+        for f in fvec {
+            self.delete_face(f);
+        }
+
+        // mark v as deleted if not yet done by delete_face()
+        self.topology.mark_deleted_vertex(v);
+    }
+
+    /// Deletes the `Edge` e and its incident faces from the `Mesh`.
+    ///
+    /// The edge is only marked as deleted, call `garbage_collection` to actually remove it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..4 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]);
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]);
+    /// let e = m.topology.edge(m.topology.find_halfedge(vvec[1],vvec[2]).unwrap());
+    /// m.delete_edge(e);
+    /// assert_eq!(m.topology.n_faces(),0);
+    /// assert_eq!(m.topology.n_edges(),0);
+    /// ```
+    pub fn delete_edge(&mut self, e : Edge) {
+        if self.topology.is_deleted_edge(e) {
+            return;
+        }
+
+        let f0 = self.topology.face(self.topology.edge_halfedge(e,0));
+        let f1 = self.topology.face(self.topology.edge_halfedge(e,1));
+
+        if let Some(f) = f0 {
+            self.delete_face(f);
+        }
+        if let Some(f) = f1 {
+            self.delete_face(f);
+        }
+    }
+
+    /// Deletes the `Face` f from the `Mesh`.
+    ///
+    /// The edges and vertices which are no longer incident to any face are deleted too.
+    /// The elements are only marked as deleted, call `garbage_collection` to actually remove them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..4 {
+    ///     vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]);
+    /// m.delete_face(f);
+    /// assert_eq!(m.topology.n_faces(),1);
+    /// assert_eq!(m.topology.n_edges(),3);
+    /// assert_eq!(m.topology.n_vertices(),3);
+    /// ```
+    pub fn delete_face(&mut self, f : Face) {
+        if self.topology.is_deleted_face(f) {
+            return;
+        }
+
+        self.topology.mark_deleted_face(f);
+
+        // boundary edges of face f to be deleted
+        let mut deleted_edges : Vec<Edge> = Vec::new();
+        // vertices of face f for updating their outgoing halfedge
+        let mut vertices : Vec<Vertex> = Vec::new();
+
+        let hvec : Vec<Halfedge> = self.topology.halfedges_around(f).collect();
+        for h in hvec {
+            self.topology.hconn_[h].face_ = Face::invalid();
+            if self.topology.is_boundary_halfedge(self.topology.opposite_halfedge(h)) {
+                deleted_edges.push(self.topology.edge(h));
+            }
+            vertices.push(self.topology.to_vertex(h));
+        }
+
+        // delete all collected (half)edges and the vertices which become isolated
+        for e in deleted_edges {
+            let h0 = self.topology.edge_halfedge(e,0);
+            let v0 = self.topology.to_vertex(h0);
+            let next0 = self.topology.next_halfedge(h0);
+            let prev0 = self.topology.prev_halfedge(h0);
+
+            let h1 = self.topology.edge_halfedge(e,1);
+            let v1 = self.topology.to_vertex(h1);
+            let next1 = self.topology.next_halfedge(h1);
+            let prev1 = self.topology.prev_halfedge(h1);
+
+            // adjust next and prev handles
+            self.topology.set_next_halfedge(prev0,next1);
+            self.topology.set_next_halfedge(prev1,next0);
+
+            self.topology.mark_deleted_edge(e);
+
+            // update v0
+            if self.topology.halfedge(v0) == Some(h1) {
+                if next0 == h1 {
+                    self.topology.mark_deleted_vertex(v0);
+                } else {
+                    self.topology.set_halfedge(v0,next0);
+                }
+            }
+
+            // update v1
+            if self.topology.halfedge(v1) == Some(h0) {
+                if next1 == h0 {
+                    self.topology.mark_deleted_vertex(v1);
+                } else {
+                    self.topology.set_halfedge(v1,next1);
+                }
+            }
+        }
+
+        // update outgoing halfedge handles of remaining vertices
+        for v in vertices {
+            self.topology.adjust_outgoing_halfedge(v);
         }
-        return vec;
     }
 
-    /// Adds a new `Face` to the `Mesh`.
+    /// Removes the deleted elements from the `Mesh`.
+    ///
+    /// The remaining elements and their properties are compacted, so all the handles
+    /// kept before the call may be invalidated.
     ///
     /// # Examples
     ///
@@ -794,149 +2515,135 @@ impl Mesh {
     ///
     /// let mut m = Mesh::new();
     /// let mut vvec = Vec::<Vertex>::new();
-    /// for _ in 0..3 {
+    /// for _ in 0..4 {
     ///     vvec.push(m.add_vertex());
     /// }
-    /// let f = m.add_face(&vvec);
-    /// # assert!(f.is_some());
-    /// ```
-    pub fn add_face(&mut self, vertices : & Vec<Vertex>) -> Option<Face> {
-        let n = vertices.len();
-        let mut hvec = Vec::<Option<Halfedge>>::new();
-        hvec.reserve(n);
-        let mut new_hvec = Vec::<bool>::new();
-        new_hvec.reserve(n);
-        let mut next_cache : Vec<(Halfedge,Halfedge)> = Vec::new();
-
-        // Does the face to add is valid
-        for i in 0..n {
-            if !self.topology.is_boundary_vertex(vertices[i]) {
-                return None;
-            }
-            hvec.push(self.topology.find_halfedge(vertices[i],vertices[(i+1)%n]));
-            new_hvec.push(hvec[i].is_none());
-            if !new_hvec[i] && !self.topology.is_boundary_halfedge(hvec[i].unwrap()) {
-                return None;
-            }
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]);
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]);
+    /// m.delete_vertex(vvec[0]);
+    /// m.garbage_collection();
+    /// assert_eq!(m.topology.vertices_size(),3);
+    /// assert_eq!(m.topology.faces_size(),1);
+    /// assert_eq!(m.topology.edges_size(),3);
+    /// ```
+    pub fn garbage_collection(&mut self) {
+        if !self.topology.has_garbage() {
+            return;
         }
 
-        // re-link patches if necessary
-        for i in 0..n {
-            let ii = (i+1)%n;
-            if !new_hvec[i] && !new_hvec[ii] {
-                let inner_prev = hvec[i].unwrap();
-                let inner_next = hvec[ii].unwrap();
-                if self.topology.next_halfedge(inner_prev) != inner_next {
-                    let outer_prev = self.topology.opposite_halfedge(inner_next);
-                    let mut boundary_prev = outer_prev;
-                    loop {
-                        boundary_prev = self.topology.opposite_halfedge(self.topology.next_halfedge(boundary_prev));;
-                        if (self.topology.is_boundary_halfedge(boundary_prev)) && boundary_prev!=inner_prev {
-                            break;
-                        }
-                    }
-                    let boundary_next = self.topology.next_halfedge(boundary_prev);
-                    assert!(self.topology.is_boundary_halfedge(boundary_prev));
-                    assert!(self.topology.is_boundary_halfedge(boundary_next));
-                    if boundary_next == inner_next {
-                        return None;
-                    }
-                    let patch_start = self.topology.next_halfedge(inner_prev);
-                    let patch_end   = self.topology.prev_halfedge(inner_next);
-                    next_cache.push((boundary_prev, patch_start));
-                    next_cache.push((patch_end, boundary_next));
-                    next_cache.push((inner_prev, inner_next));
-                }
+        let mut nv = self.topology.vertices_size();
+        let mut ne = self.topology.edges_size();
+        let mut nf = self.topology.faces_size();
+
+        // setup handle mapping
+        let mut vmap : Vec<Vertex> = (0..nv).map(Vertex::new).collect();
+        let mut hmap : Vec<Halfedge> = (0..2*ne).map(Halfedge::new).collect();
+        let mut fmap : Vec<Face> = (0..nf).map(Face::new).collect();
+
+        // remove deleted vertices
+        if nv > 0 {
+            let mut i0 = 0;
+            let mut i1 = nv-1;
+            loop {
+                // find first deleted and last un-deleted
+                while !self.topology.vdeleted_[Vertex::new(i0)] && i0 < i1 {i0 += 1;}
+                while self.topology.vdeleted_[Vertex::new(i1)] && i0 < i1 {i1 -= 1;}
+                if i0 >= i1 {break;}
+
+                // swap
+                self.topology.vconn_.swap(i0,i1);
+                self.topology.vdeleted_.swap(i0,i1);
+                self.properties.vprop_.swap(i0,i1);
+                vmap.swap(i0,i1);
             }
+            nv = if self.topology.vdeleted_[Vertex::new(i0)] {i0} else {i0+1};
         }
 
-
-        // Create missing edges
-        for i in 0..n {
-            if new_hvec[i] {
-                let ii = (i+1)%n;
-                hvec[i] = Some(self.new_edge(vertices[i], vertices[ii]));
+        // remove deleted edges
+        if ne > 0 {
+            let mut i0 = 0;
+            let mut i1 = ne-1;
+            loop {
+                // find first deleted and last un-deleted
+                while !self.topology.edeleted_[Edge::new(i0)] && i0 < i1 {i0 += 1;}
+                while self.topology.edeleted_[Edge::new(i1)] && i0 < i1 {i1 -= 1;}
+                if i0 >= i1 {break;}
+
+                // swap
+                self.topology.hconn_.swap(2*i0,2*i1);
+                self.topology.hconn_.swap(2*i0+1,2*i1+1);
+                self.topology.edeleted_.swap(i0,i1);
+                self.properties.hprop_.swap(2*i0,2*i1);
+                self.properties.hprop_.swap(2*i0+1,2*i1+1);
+                self.properties.eprop_.swap(i0,i1);
+                hmap.swap(2*i0,2*i1);
+                hmap.swap(2*i0+1,2*i1+1);
             }
+            ne = if self.topology.edeleted_[Edge::new(i0)] {i0} else {i0+1};
         }
 
-        // Creates the new face
-        self.properties.fprop_.push();
-        self.topology.fconn_.push();
-        let f = Face::new(self.topology.fconn_.len()-1);
-        self.topology.fconn_[f].halfedge_ = hvec[n-1].unwrap();
-
-        // Setup halfedges
-        let mut needs_adjust : Vec<bool> = Vec::new();
-        needs_adjust.resize(n,false);
-        for i in 0..n {
-            let ii = (i+1)%n;
-            let v = vertices[ii];
-            let inner_prev = hvec[i].unwrap();
-            let inner_next = hvec[ii].unwrap();
-
-            if new_hvec[i] || new_hvec[ii] {
-                let outer_prev = self.topology.opposite_halfedge(inner_next);
-                let outer_next = self.topology.opposite_halfedge(inner_prev);
-
-                if !new_hvec[ii] { // prev is new, next is not
-                    let boundary_prev = self.topology.prev_halfedge(inner_next);
-                    next_cache.push((boundary_prev,outer_next));
-                    self.topology.set_halfedge(v,outer_next);
-                } else if !new_hvec[i] { // next is new, prev is not
-                    let boundary_next = self.topology.next_halfedge(inner_prev);
-                    next_cache.push((outer_prev,boundary_next));
-                    self.topology.set_halfedge(v,boundary_next);
-                } else { // both are new
-                    match self.topology.halfedge(v) {
-                        None => {
-                            self.topology.set_halfedge(v,outer_next);
-                            next_cache.push((outer_prev,outer_next));
-                        }
-                        Some(h) => {
-                            let boundary_prev = self.topology.prev_halfedge(h);
-                            next_cache.push((boundary_prev,outer_next));
-                            next_cache.push((outer_prev,h));
-                        }
-                    }
-                }
-                next_cache.push((inner_prev,inner_next));
-            } else {
-                needs_adjust[ii] = self.topology.halfedge(v).unwrap() == inner_next;
+        // remove deleted faces
+        if nf > 0 {
+            let mut i0 = 0;
+            let mut i1 = nf-1;
+            loop {
+                // find first deleted and last un-deleted
+                while !self.topology.fdeleted_[Face::new(i0)] && i0 < i1 {i0 += 1;}
+                while self.topology.fdeleted_[Face::new(i1)] && i0 < i1 {i1 -= 1;}
+                if i0 >= i1 {break;}
+
+                // swap
+                self.topology.fconn_.swap(i0,i1);
+                self.topology.fdeleted_.swap(i0,i1);
+                self.properties.fprop_.swap(i0,i1);
+                fmap.swap(i0,i1);
             }
-            self.topology.set_face(inner_prev,f);
+            nf = if self.topology.fdeleted_[Face::new(i0)] {i0} else {i0+1};
         }
 
-        // process cache
-        for (first,second) in next_cache {
-            self.topology.set_next_halfedge(first,second);
+        // update vertex connectivity
+        for i in 0..nv {
+            let v = Vertex::new(i);
+            if let Some(h) = self.topology.halfedge(v) {
+                self.topology.set_halfedge(v,hmap[h.idx()]);
+            }
         }
 
-        // adjust vertices halfedge handle
-        for i in 0..n {
-            if needs_adjust[i] {
-                self.topology.adjust_outgoing_halfedge(vertices[i]);
+        // update halfedge connectivity
+        for i in 0..2*ne {
+            let h = Halfedge::new(i);
+            let v = self.topology.to_vertex(h);
+            self.topology.set_vertex(h,vmap[v.idx()]);
+            let nh = self.topology.next_halfedge(h);
+            self.topology.set_next_halfedge(h,hmap[nh.idx()]);
+            if let Some(f) = self.topology.face(h) {
+                self.topology.set_face(h,fmap[f.idx()]);
             }
         }
 
-        return Some(f);
-    }
-
-    /// allocate a new edge and returns the `Halfedge` from start to end
-    fn new_edge(&mut self, start : Vertex, end : Vertex) -> Halfedge {
-        assert!(start != end);
-
-        self.properties.eprop_.push();
-        self.properties.hprop_.push();
-        self.properties.hprop_.push();
-        self.topology.hconn_.push();
-        let h0 = Halfedge::new(self.topology.hconn_.len()-1);
-        self.topology.hconn_.push();
-        let h1 = Halfedge::new(self.topology.hconn_.len()-1);
-
-        self.topology.set_vertex(h0, end);
-        self.topology.set_vertex(h1, start);
+        // update face connectivity
+        for i in 0..nf {
+            let f = Face::new(i);
+            let h = self.topology.face_halfedge(f);
+            self.topology.fconn_[f].halfedge_ = hmap[h.idx()];
+        }
 
-        return h0;
+        // finally resize arrays
+        self.topology.vconn_.resize(nv);
+        self.topology.vdeleted_.resize(nv);
+        self.properties.vprop_.resize(nv);
+        self.topology.hconn_.resize(2*ne);
+        self.topology.edeleted_.resize(ne);
+        self.properties.hprop_.resize(2*ne);
+        self.properties.eprop_.resize(ne);
+        self.topology.fconn_.resize(nf);
+        self.topology.fdeleted_.resize(nf);
+        self.properties.fprop_.resize(nf);
+
+        self.topology.deleted_vertices_ = 0;
+        self.topology.deleted_edges_ = 0;
+        self.topology.deleted_faces_ = 0;
+        self.topology.garbage_ = false;
     }
 }
 
@@ -1142,7 +2849,7 @@ impl<D : 'static> IndexMut<(PropertyHalfedge<D>,Halfedge)> for Properties {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mesh_iterator::VerticesAround;
+    use validation::TopologyError;
 
     fn add_face_and_test(m : &mut Mesh, vvec : &Vec<Vertex>) {
         let f_nb = m.topology.n_faces();
@@ -1236,6 +2943,314 @@ mod tests {
         add_face_and_test(&mut m,&vec![v0,v6,v1]);
     }
 
+    #[test]
+    fn delete_face() {
+        let mut m = Mesh::new();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        let v3 = m.add_vertex();
+        let f0 = m.add_face(&vec![v0,v1,v2]).unwrap();
+        let f1 = m.add_face(&vec![v2,v1,v3]).unwrap();
+
+        m.delete_face(f0);
+        assert!(m.topology.is_deleted_face(f0));
+        assert!(!m.topology.is_deleted_face(f1));
+        assert!(m.topology.is_deleted_vertex(v0));
+        assert_eq!(m.topology.n_faces(),1);
+        assert_eq!(m.topology.n_edges(),3);
+        assert_eq!(m.topology.n_vertices(),3);
+        let h = m.topology.find_halfedge(v1,v2).unwrap();
+        assert!(m.topology.is_boundary_halfedge(h));
+        assert!(m.topology.is_boundary_vertex(v1));
+        assert!(m.topology.is_boundary_vertex(v2));
+
+        m.delete_face(f1);
+        assert_eq!(m.topology.n_faces(),0);
+        assert_eq!(m.topology.n_edges(),0);
+        assert_eq!(m.topology.n_vertices(),0);
+    }
+
+    #[test]
+    fn delete_vertex_and_add_face() {
+        let mut m = Mesh::new();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        let v3 = m.add_vertex();
+        m.add_face(&vec![v0,v1,v2]).unwrap();
+        m.add_face(&vec![v2,v1,v3]).unwrap();
+
+        m.delete_vertex(v3);
+        assert_eq!(m.topology.n_faces(),1);
+        assert_eq!(m.topology.n_vertices(),3);
+        let v4 = m.add_vertex();
+        add_face_and_test(&mut m,&vec![v2,v1,v4]);
+    }
+
+    #[test]
+    fn garbage_collection() {
+        let mut m = Mesh::new();
+        let vprop = m.properties.add_vertex_property::<usize>("v:my_prop",0).unwrap();
+        let fprop = m.properties.add_face_property::<usize>("f:my_prop",0).unwrap();
+        let mut vvec = Vec::<Vertex>::new();
+        for i in 0..5 {
+            let v = m.add_vertex();
+            m.properties[(vprop,v)] = i;
+            vvec.push(v);
+        }
+        let f0 = m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+        let f1 = m.add_face(&vec![vvec[2],vvec[1],vvec[3]]).unwrap();
+        let f2 = m.add_face(&vec![vvec[3],vvec[1],vvec[4]]).unwrap();
+        m.properties[(fprop,f0)] = 0;
+        m.properties[(fprop,f1)] = 1;
+        m.properties[(fprop,f2)] = 2;
+
+        m.delete_vertex(vvec[0]);
+        m.garbage_collection();
+        assert!(!m.topology.has_garbage());
+        assert_eq!(m.topology.vertices_size(),4);
+        assert_eq!(m.topology.faces_size(),2);
+        assert_eq!(m.topology.edges_size(),5);
+
+        let mut values : Vec<usize> = m.topology.vertices().map(|v| m.properties[(vprop,v)]).collect();
+        values.sort();
+        assert_eq!(values,vec![1,2,3,4]);
+        for f in m.topology.faces() {
+            let fvalues : Vec<usize> = m.topology.vertices_around(f).map(|v| m.properties[(vprop,v)]).collect();
+            match m.properties[(fprop,f)] {
+                1 => assert_eq!(fvalues,vec![2,1,3]),
+                2 => assert_eq!(fvalues,vec![3,1,4]),
+                _ => assert!(false),
+            }
+        }
+        for h in m.topology.halfedges() {
+            assert!(m.topology.prev_halfedge(m.topology.next_halfedge(h)) == h);
+        }
+    }
+
+    #[test]
+    fn flip() {
+        let mut m = Mesh::new();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        let v3 = m.add_vertex();
+        let f0 = m.add_face(&vec![v0,v1,v2]).unwrap();
+        let f1 = m.add_face(&vec![v2,v1,v3]).unwrap();
+
+        let e = m.topology.edge(m.topology.find_halfedge(v1,v2).unwrap());
+        assert!(m.topology.is_flip_ok(e));
+        m.topology.flip(e);
+        let h = m.topology.find_halfedge(v3,v0).unwrap();
+        assert!(m.topology.edge(h) == e);
+
+        let vvec0 : Vec<_> = m.topology.vertices_around(f0).collect();
+        let vvec1 : Vec<_> = m.topology.vertices_around(f1).collect();
+        assert_eq!(vvec0.len(),3);
+        assert_eq!(vvec1.len(),3);
+        assert!(vvec0.contains(&v0) && vvec0.contains(&v3));
+        assert!(vvec1.contains(&v0) && vvec1.contains(&v3));
+        for h in m.topology.halfedges() {
+            assert!(m.topology.prev_halfedge(m.topology.next_halfedge(h)) == h);
+            if let Some(f) = m.topology.face(h) {
+                assert!(m.topology.vertices_around(f).any(|v| v == m.topology.to_vertex(h)));
+            }
+        }
+        for v in m.topology.vertices() {
+            let h = m.topology.halfedge(v).unwrap();
+            assert!(m.topology.from_vertex(h) == v);
+            assert!(m.topology.is_boundary_halfedge(h));
+        }
+
+        // flipping back restores the original edge
+        m.topology.flip(e);
+        assert!(m.topology.find_halfedge(v1,v2).is_some());
+        assert!(m.topology.find_halfedge(v0,v3).is_none());
+    }
+
+    #[test]
+    fn flip_not_ok() {
+        let mut m = Mesh::new();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        let v3 = m.add_vertex();
+        m.add_face(&vec![v0,v1,v2]).unwrap();
+        m.add_face(&vec![v0,v2,v3]).unwrap();
+        m.add_face(&vec![v0,v3,v1]).unwrap();
+        m.add_face(&vec![v1,v3,v2]).unwrap();
+
+        // every flip in a tetrahedron creates an existing edge
+        for e in m.topology.edges() {
+            assert!(!m.topology.is_flip_ok(e));
+        }
+    }
+
+    fn check_connectivity(m : &Mesh) {
+        assert_eq!(m.topology.validate().errors,vec![]);
+        for h in m.topology.halfedges() {
+            assert!(m.topology.prev_halfedge(m.topology.next_halfedge(h)) == h);
+            assert!(m.topology.from_vertex(m.topology.next_halfedge(h)) == m.topology.to_vertex(h));
+            assert!(m.topology.face(m.topology.next_halfedge(h)) == m.topology.face(h));
+        }
+        for f in m.topology.faces() {
+            assert!(m.topology.face(m.topology.face_halfedge(f)) == Some(f));
+        }
+        for v in m.topology.vertices() {
+            if let Some(h) = m.topology.halfedge(v) {
+                assert!(m.topology.from_vertex(h) == v);
+                let boundary = m.topology.halfedges_around(v).any(|hh| m.topology.is_boundary_halfedge(hh));
+                assert_eq!(boundary,m.topology.is_boundary_halfedge(h));
+            }
+        }
+    }
+
+    #[test]
+    fn split_edge() {
+        let mut m = Mesh::new();
+        let eprop = m.properties.add_edge_property::<u32>("e:my_prop",17).unwrap();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        let v3 = m.add_vertex();
+        m.add_face(&vec![v0,v1,v2]).unwrap();
+        m.add_face(&vec![v2,v1,v3]).unwrap();
+        for e in m.topology.edges() {
+            m.properties[(eprop,e)] = 42;
+        }
+
+        // interior edge
+        let e = m.topology.edge(m.topology.find_halfedge(v1,v2).unwrap());
+        let v = m.add_vertex();
+        let h = m.split_edge(e,v);
+        assert!(m.topology.to_vertex(h) == v);
+        assert_eq!(m.topology.n_faces(),4);
+        assert_eq!(m.topology.n_edges(),8);
+        check_connectivity(&m);
+        assert!(!m.topology.is_boundary_vertex(v));
+        assert_eq!(m.topology.vertices_around(v).count(),4);
+        let n_default = m.topology.edges().filter(|&e| m.properties[(eprop,e)] == 17).count();
+        assert_eq!(n_default,3);
+        for f in m.topology.faces() {
+            assert_eq!(m.topology.vertices_around(f).count(),3);
+        }
+
+        // boundary edge
+        let e = m.topology.edge(m.topology.find_halfedge(v0,v1).unwrap());
+        let w = m.add_vertex();
+        m.split_edge(e,w);
+        assert_eq!(m.topology.n_faces(),5);
+        assert_eq!(m.topology.n_edges(),10);
+        check_connectivity(&m);
+        assert!(m.topology.is_boundary_vertex(w));
+        assert_eq!(m.topology.vertices_around(w).count(),3);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic]
+    fn split_edge_of_quad() {
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(4);
+        m.add_face(&vvec).unwrap();
+        let e = m.topology.edge(m.topology.find_halfedge(vvec[0],vvec[1]).unwrap());
+        let v = m.add_vertex();
+        m.split_edge(e,v);
+    }
+
+    #[test]
+    fn split_face() {
+        let mut m = Mesh::new();
+        let fprop = m.properties.add_face_property::<u32>("f:my_prop",17).unwrap();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        let v3 = m.add_vertex();
+        let v4 = m.add_vertex();
+        let f = m.add_face(&vec![v0,v1,v2,v3]).unwrap();
+        m.add_face(&vec![v2,v1,v4]).unwrap();
+        m.properties[(fprop,f)] = 42;
+
+        let v = m.add_vertex();
+        m.split_face(f,v);
+        assert_eq!(m.topology.n_faces(),5);
+        assert_eq!(m.topology.n_edges(),10);
+        check_connectivity(&m);
+        assert!(!m.topology.is_boundary_vertex(v));
+        let vvec : Vec<_> = m.topology.vertices_around(v).collect();
+        assert_eq!(vvec.len(),4);
+        for f in m.topology.faces_around(v) {
+            assert_eq!(m.topology.vertices_around(f).count(),3);
+        }
+        assert_eq!(m.properties[(fprop,f)],42);
+        let n_default = m.topology.faces().filter(|&f| m.properties[(fprop,f)] == 17).count();
+        assert_eq!(n_default,4);
+    }
+
+    #[test]
+    fn collapse() {
+        let mut m = Mesh::new();
+        let vprop = m.properties.add_vertex_property::<usize>("v:my_prop",0).unwrap();
+        let vc = m.add_vertex();
+        let mut ring = Vec::<Vertex>::new();
+        for i in 0..6 {
+            let v = m.add_vertex();
+            m.properties[(vprop,v)] = i+1;
+            ring.push(v);
+        }
+        for i in 0..6 {
+            m.add_face(&vec![vc,ring[i],ring[(i+1)%6]]).unwrap();
+        }
+
+        let h = m.topology.find_halfedge(vc,ring[0]).unwrap();
+        assert!(m.topology.is_collapse_ok(h));
+        m.topology.collapse(h);
+        assert!(m.topology.is_deleted_vertex(vc));
+        assert_eq!(m.topology.n_vertices(),6);
+        assert_eq!(m.topology.n_faces(),4);
+        assert_eq!(m.topology.n_edges(),9);
+        check_connectivity(&m);
+        assert_eq!(m.topology.vertices_around(ring[0]).count(),5);
+
+        m.garbage_collection();
+        check_connectivity(&m);
+        assert_eq!(m.topology.vertices_size(),6);
+        assert_eq!(m.topology.faces_size(),4);
+        assert_eq!(m.topology.edges_size(),9);
+        let mut values : Vec<usize> = m.topology.vertices().map(|v| m.properties[(vprop,v)]).collect();
+        values.sort();
+        assert_eq!(values,vec![1,2,3,4,5,6]);
+    }
+
+    #[test]
+    fn collapse_not_ok() {
+        let mut m = Mesh::new();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        m.add_face(&vec![v0,v1,v2]).unwrap();
+
+        // collapsing an isolated triangle would produce a degenerate face
+        for h in m.topology.halfedges() {
+            assert!(!m.topology.is_collapse_ok(h));
+        }
+
+        // the one-rings of vc and ring[0] share ring[3], which is not opposite to the edge
+        let mut m = Mesh::new();
+        let vc = m.add_vertex();
+        let ring = m.add_vertices(6);
+        for i in 0..6 {
+            m.add_face(&vec![vc,ring[i],ring[(i+1)%6]]).unwrap();
+        }
+        let v = m.add_vertex();
+        m.add_face(&vec![ring[0],ring[3],v]).unwrap();
+        let h = m.topology.find_halfedge(vc,ring[0]).unwrap();
+        assert!(!m.topology.is_collapse_ok(h));
+        let h = m.topology.find_halfedge(vc,ring[1]).unwrap();
+        assert!(m.topology.is_collapse_ok(h));
+    }
+
     #[test]
     fn property() {
         let mut m = Mesh::new();
@@ -1283,4 +3298,72 @@ mod tests {
         let v0 = m.add_vertex();
         m.properties[(prop,v0)];
     }
+
+    #[test]
+    fn clone() {
+        let mut m = Mesh::new();
+        let prop = m.properties.add_vertex_property::<u32>("v:my_prop",17).unwrap();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        m.add_face(&vec![v0,v1,v2]).unwrap();
+        let mut copy = m.clone();
+        copy.properties[(prop,v0)] = 42;
+        copy.delete_vertex(v0);
+        copy.garbage_collection();
+        assert_eq!(copy.topology.n_vertices(),0);
+        assert_eq!(copy.topology.n_faces(),0);
+        assert_eq!(m.topology.n_vertices(),3);
+        assert_eq!(m.topology.n_faces(),1);
+        assert_eq!(m.properties[(prop,v0)],17);
+        check_connectivity(&m);
+    }
+
+    #[test]
+    fn diverging_clones() {
+        let mut m = Mesh::new();
+        let v0 = m.add_vertex();
+        let mut copy = m.clone();
+        let prop = m.properties.add_vertex_property::<u32>("v:my_prop",17).unwrap();
+        let other = copy.properties.add_vertex_property::<u32>("v:other_prop",42).unwrap();
+        assert_eq!(prop.idx(),other.idx());
+        assert_eq!(copy.properties.try_get(prop,v0),Err(PropertyError::Removed));
+        assert_eq!(m.properties.try_get(other,v0),Err(PropertyError::Removed));
+        assert_eq!(m.properties[(prop,v0)],17);
+        assert_eq!(copy.properties[(other,v0)],42);
+    }
+
+    #[test]
+    fn validate() {
+        let mut m = Mesh::new();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        let v3 = m.add_vertex();
+        let f0 = m.add_face(&vec![v0,v1,v2]).unwrap();
+        let f1 = m.add_face(&vec![v2,v1,v3]).unwrap();
+        assert!(m.topology.validate().is_valid());
+
+        let h = m.topology.find_halfedge(v1,v2).unwrap();
+        let mut m2 = m.clone();
+        m2.topology.set_halfedge(v1,h);
+        assert_eq!(m2.topology.validate().errors,vec![TopologyError::BoundaryHalfedgeNotOutgoing(v1)]);
+
+        let mut m2 = m.clone();
+        m2.topology.set_face(m.topology.opposite_halfedge(h),f0);
+        let errors = m2.topology.validate().errors;
+        assert!(errors.contains(&TopologyError::FaceMismatch(m.topology.opposite_halfedge(h))));
+        assert!(errors.contains(&TopologyError::OpenFaceLoop(f1)));
+
+        let mut m2 = m.clone();
+        m2.topology.set_vertex(h,v3);
+        let errors = m2.topology.validate().errors;
+        assert!(errors.contains(&TopologyError::OppositeMismatch(m.topology.next_halfedge(h))));
+        assert!(errors.contains(&TopologyError::OppositeMismatch(m.topology.opposite_halfedge(h))));
+
+        let mut m2 = m.clone();
+        let hn = m.topology.next_halfedge(h);
+        m2.topology.hconn_[hn].prev_halfedge_ = hn;
+        assert!(m2.topology.validate().errors.contains(&TopologyError::NextPrevMismatch(h)));
+    }
 }
@@ -1,13 +1,19 @@
 use std::path::Path;
 use std::fs::File;
 use std::io;
+use std::io::Write;
 use std::ffi::OsString;
+use std::collections::HashMap;
 use std::io::BufReader;
+use std::io::BufWriter;
 use mesh::*;
 use handle::*;
 use lwobj::*;
 use nalgebra::Vector3;
-use mesh_iterator::VerticesAround;
+use geometry::{Point, Scalar};
+use mesh_iterator::HalfedgesAround;
+use ply::PlyFormat;
+use stl::StlFormat;
 
 #[derive(Debug)]
 pub enum MeshLoadingError {
@@ -15,6 +21,7 @@ pub enum MeshLoadingError {
     MissingPosition,
     NotManifold,
     UnknwonExtension(OsString),
+    InvalidFormat(String),
     Io(io::Error),
     Obj(LoadingError)
 }
@@ -31,25 +38,110 @@ impl From<LoadingError> for MeshLoadingError {
     }
 }
 
+/// Returns an `InvalidFormat` error with the message `msg`.
+pub fn invalid<T>(msg : String) -> Result<T,MeshLoadingError> {
+    Err(MeshLoadingError::InvalidFormat(msg))
+}
+
+/// Upper bound of the number of elements reserved from the counts declared in a file header,
+/// which are not trusted. The storage still grows as needed past this bound.
+pub const RESERVE_LIMIT : usize = 1 << 16;
+
+/// Adds a face read from a file to the `Mesh`, reversing its orientation if needed.
+pub fn add_loaded_face(m : &mut Mesh, vvec : &mut Vec<Vertex>) -> Result<Face,MeshLoadingError> {
+    if vvec.len() < 3 {
+        return invalid(format!("invalid face with {} vertices",vvec.len()));
+    }
+    for v in vvec.iter() {
+        if m.topology.vertices_size() <= v.idx() {
+            return invalid(format!("invalid vertex index {}",v.idx()));
+        }
+    }
+    if let Some(f) = m.add_face(vvec) {
+        return Ok(f);
+    }
+    vvec.reverse();
+    match m.add_face(vvec) {
+        Some(f) => Ok(f),
+        None => Err(MeshLoadingError::NotManifold),
+    }
+}
+
+/// Returns the vertex positions of an OBJ file at double precision and the group of each face,
+/// as the names given by the last `g` statement.
+///
+/// lwobj reads the positions as `f32` and skips the `g` statements, so they are read separately.
+fn obj_positions_and_groups(text : &str) -> Result<(Vec<[f64;3]>,Vec<String>),MeshLoadingError> {
+    let mut positions = Vec::new();
+    let mut groups = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let mut p = [0.;3];
+                for c in p.iter_mut() {
+                    match tokens.next().map(|x| x.parse::<f64>()) {
+                        Some(Ok(x)) => *c = x,
+                        _ => return invalid(format!("invalid OBJ vertex \"{}\"",line)),
+                    }
+                }
+                positions.push(p);
+            },
+            Some("g") => current = tokens.collect::<Vec<&str>>().join(" "),
+            Some("f") => groups.push(current.clone()),
+            _ => (),
+        }
+    }
+    Ok((positions,groups))
+}
+
 impl Mesh {
     /// Load `Mesh` from file base on the extension.
     ///
+    /// The vertices of STL files are merged only if they have identical coordinates.
+    ///
     /// # Examples
     ///
     /// ```
     /// use lwmesh::Mesh;
+    /// use std::path::PathBuf;
     ///
     /// let mut m = Mesh::load("cube.obj").ok().unwrap();
+    /// let path = PathBuf::from(".").join("cube.obj");
+    /// let mut m = Mesh::load(&path).ok().unwrap();
+    /// ```
+    pub fn load<P : AsRef<Path>>(filename : P) -> Result<Mesh,MeshLoadingError> {
+        Mesh::load_as::<Vector3<f32>,P>(filename)
+    }
+
+    /// Load `Mesh` from file base on the extension, storing the vertex positions as `P`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::load_as::<[f64;3],_>("cube.obj").ok().unwrap();
+    /// assert!(m.positions::<[f64;3]>().is_some());
     /// ```
-    pub fn load(filename : & 'static str) -> Result<Mesh,MeshLoadingError> {
-        let path = Path::new(filename);
+    pub fn load_as<P : Point, F : AsRef<Path>>(filename : F) -> Result<Mesh,MeshLoadingError> {
+        let path = filename.as_ref();
         match path.extension() {
             None => Err(MeshLoadingError::NoExtension),
             Some(ext) => {
                 if ext == "obj" {
-                    let f = try!(File::open(filename));
-                    let mut input = BufReader::new(f);
-                    return Mesh::load_obj(&mut input);
+                    let f = try!(File::open(path));
+                    return Mesh::load_obj_as::<P,_>(f);
+                } else if ext == "ply" {
+                    let f = try!(File::open(path));
+                    return Mesh::load_ply_as::<P,_>(f);
+                } else if ext == "stl" {
+                    let f = try!(File::open(path));
+                    return Mesh::load_stl_as::<P,_>(f,0.);
+                } else if ext == "off" {
+                    let f = try!(File::open(path));
+                    return Mesh::load_off_as::<P,_>(f);
                 } else {
                     return Err(MeshLoadingError::UnknwonExtension(ext.to_os_string()));
                 }
@@ -57,60 +149,170 @@ impl Mesh {
         }
     }
 
-    /// Load Obj `Mesh` from  a `BufReader`.
+    /// Load Obj `Mesh` from any `Read`.
+    ///
+    /// Texture coordinates and normals referenced by the faces are stored in the
+    /// `h:texcoord` and `h:normal` halfedge properties, on the halfedge pointing to
+    /// the corner vertex. When only some corners have texture coordinates or normals, the
+    /// `h:has_texcoord` and `h:has_normal` halfedge properties are `false` for the corners
+    /// without them. The name of the object and of the group containing each face
+    /// are stored in the `f:object` and `f:group` face properties when the file declares
+    /// named objects or groups.
     ///
     /// # Examples
     ///
     /// ```
     /// use lwmesh::Mesh;
     ///
-    /// let mut m = Mesh::load("cube.obj").ok().unwrap();
+    /// let input = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    /// let mut m = Mesh::load_obj(input.as_bytes()).ok().unwrap();
+    /// assert_eq!(m.topology.n_faces(),1);
     /// ```
-    pub fn load_obj<R : io::Read>(input : &mut BufReader<R>) -> Result<Mesh,MeshLoadingError> {
-        let obj: ObjData = try!(ObjData::load(input));
+    pub fn load_obj<R : io::Read>(input : R) -> Result<Mesh,MeshLoadingError> {
+        Mesh::load_obj_as::<Vector3<f32>,R>(input)
+    }
+
+    /// Load Obj `Mesh` from any `Read`, storing the vertex positions as `P`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let input = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    /// let mut m = Mesh::load_obj_as::<[f64;3],_>(input.as_bytes()).ok().unwrap();
+    /// let pos = m.positions::<[f64;3]>().unwrap();
+    /// assert_eq!(m.properties[(pos,Vertex::new(1))],[1.,0.,0.]);
+    /// ```
+    pub fn load_obj_as<P : Point, R : io::Read>(mut input : R) -> Result<Mesh,MeshLoadingError> {
+        let mut text = String::new();
+        try!(input.read_to_string(&mut text));
+        let obj: ObjData = try!(ObjData::load(&mut BufReader::new(text.as_bytes())));
+        let (positions,groups) = try!(obj_positions_and_groups(&text));
+        if positions.len() != obj.vertices.len() {
+            return invalid(format!("invalid OBJ vertices, {} read out of {}",positions.len(),obj.vertices.len()));
+        }
 
         let mut m = Mesh::new();
-        let pos = m.properties.add_vertex_property::<Vector3<f32>>("v:position",Vector3::new(0f32,0f32,0f32)).unwrap();
-        for (x,y,z,_) in obj.vertices {
+        let pos = m.add_positions::<P>().unwrap();
+        for p in positions {
             let v = m.add_vertex();
-            m.properties[(pos,v)] = Vector3::new(x,y,z);
+            m.properties[(pos,v)] = P::from_coords(Scalar::from_f64(p[0]),Scalar::from_f64(p[1]),Scalar::from_f64(p[2]));
         }
-        for f in obj.faces {
+
+        let has_texcoord = obj.faces.iter().any(|f| f.iter().any(|&(_,t,_)| t.is_some()));
+        let has_normal = obj.faces.iter().any(|f| f.iter().any(|&(_,_,n)| n.is_some()));
+        let texprop = if has_texcoord {
+            m.properties.add_halfedge_property::<Vector3<f32>>("h:texcoord",Vector3::new(0f32,0f32,0f32))
+        } else {
+            None
+        };
+        let nprop = if has_normal {
+            m.properties.add_halfedge_property::<Vector3<f32>>("h:normal",Vector3::new(0f32,0f32,0f32))
+        } else {
+            None
+        };
+        // the corners without data are only marked when other corners have some
+        let texset = if has_texcoord && obj.faces.iter().any(|f| f.iter().any(|&(_,t,_)| t.is_none())) {
+            m.properties.add_halfedge_property::<bool>("h:has_texcoord",true)
+        } else {
+            None
+        };
+        let nset = if has_normal && obj.faces.iter().any(|f| f.iter().any(|&(_,_,n)| n.is_none())) {
+            m.properties.add_halfedge_property::<bool>("h:has_normal",true)
+        } else {
+            None
+        };
+
+        let mut faces : Vec<Face> = Vec::with_capacity(obj.faces.len());
+        for f in obj.faces.iter() {
             let mut vvec : Vec<Vertex> = Vec::new();
-            for (i,_,_) in f {
+            for &(i,_,_) in f.iter() {
                 vvec.push(Vertex::new(i));
             }
+            let face = try!(add_loaded_face(&mut m,&mut vvec));
+            faces.push(face);
 
-            let f = m.add_face(&vvec);
-            if f.is_none() {
-                vvec.reverse();
-                let f = m.add_face(&vvec);
-                if f.is_none() {
-                    return Err(MeshLoadingError::NotManifold);
+            if texprop.is_none() && nprop.is_none() {
+                continue;
+            }
+            let hvec : Vec<Halfedge> = m.topology.halfedges_around(face).collect();
+            for h in hvec {
+                let v = m.topology.to_vertex(h);
+                let &(_,t,n) = f.iter().find(|&&(i,_,_)| i == v.idx()).unwrap();
+                if let (Some(prop),Some(t)) = (texprop,t) {
+                    match obj.texcoords.get(t) {
+                        Some(&(x,y,z)) => m.properties[(prop,h)] = Vector3::new(x,y,z),
+                        None => return Err(MeshLoadingError::InvalidFormat(format!("invalid texture coordinate index {}",t))),
+                    }
+                }
+                if let (Some(prop),Some(n)) = (nprop,n) {
+                    match obj.normals.get(n) {
+                        Some(&(x,y,z)) => m.properties[(prop,h)] = Vector3::new(x,y,z),
+                        None => return Err(MeshLoadingError::InvalidFormat(format!("invalid normal index {}",n))),
+                    }
+                }
+                if let (Some(prop),None) = (texset,t) {
+                    m.properties[(prop,h)] = false;
+                }
+                if let (Some(prop),None) = (nset,n) {
+                    m.properties[(prop,h)] = false;
                 }
             }
         }
+
+        if obj.objects.iter().any(|o| !o.name.is_empty()) {
+            let oprop = m.properties.add_face_property::<String>("f:object",String::new()).unwrap();
+            for o in obj.objects.iter() {
+                for &p in o.primitives.iter() {
+                    if let Some(&f) = faces.get(p) {
+                        m.properties[(oprop,f)] = o.name.clone();
+                    }
+                }
+            }
+        }
+        if groups.iter().any(|g| !g.is_empty()) {
+            // the groups are matched to the faces read by lwobj by their order
+            if groups.len() != faces.len() {
+                return invalid(format!("invalid OBJ groups, {} faces are grouped out of {}",groups.len(),faces.len()));
+            }
+            let gprop = m.properties.add_face_property::<String>("f:group",String::new()).unwrap();
+            for (&f,g) in faces.iter().zip(groups) {
+                m.properties[(gprop,f)] = g;
+            }
+        }
         return Ok(m);
     }
 
     /// Write `Mesh` base on the extension.
     ///
+    /// PLY files are written in binary little endian format and STL files in binary format.
+    ///
     /// # Examples
     ///
     /// ```
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::load("cube.obj").ok().unwrap();
+    /// m.write(String::from("cube2.obj"));
     /// ```
-    pub fn write(&self, filename : & 'static str) -> Result<(),MeshLoadingError> {
-        let path = Path::new(filename);
+    pub fn write<P : AsRef<Path>>(&self, filename : P) -> Result<(),MeshLoadingError> {
+        let path = filename.as_ref();
         match path.extension() {
             None => Err(MeshLoadingError::NoExtension),
             Some(ext) => {
                 if ext == "obj" {
-                    let f = try!(File::create(filename));
-                    let mut output = io::BufWriter::new(f);
-                    return self.write_obj(&mut output);
+                    let f = try!(File::create(path));
+                    return self.write_obj(f);
+                } else if ext == "ply" {
+                    let f = try!(File::create(path));
+                    return self.write_ply(f,PlyFormat::BinaryLittleEndian);
+                } else if ext == "stl" {
+                    let f = try!(File::create(path));
+                    return self.write_stl(f,StlFormat::Binary);
+                } else if ext == "off" {
+                    let f = try!(File::create(path));
+                    return self.write_off(f);
                 } else {
                     return Err(MeshLoadingError::UnknwonExtension(ext.to_os_string()));
                 }
@@ -118,7 +320,12 @@ impl Mesh {
         }
     }
 
-    /// Write Obj `Mesh` into a `BufWriter`.
+    /// Write Obj `Mesh` into any `Write`.
+    ///
+    /// The positions can be stored as `Vector3<f32>`, `Vector3<f64>`, `[f32;3]` or `[f64;3]`,
+    /// and are written at their precision. The `h:texcoord`, `h:normal`, `f:object` and `f:group` properties are written back when present.
+    /// The texture coordinates and normals of the corners for which `h:has_texcoord` and `h:has_normal`
+    /// are `false` are not written.
     ///
     /// # Examples
     ///
@@ -126,33 +333,114 @@ impl Mesh {
     /// use lwmesh::Mesh;
     ///
     /// let mut m = Mesh::load("cube.obj").ok().unwrap();
-    /// m.write("cube2.obj");
+    /// let mut output = Vec::<u8>::new();
+    /// assert!(m.write_obj(&mut output).is_ok());
     /// ```
-    pub fn write_obj<R : io::Write>(&self, output : &mut io::BufWriter<R>) -> Result<(),MeshLoadingError> {
-        let mut obj_data: ObjData = ObjData::new();
-        let posprop = match self.properties.get_vertex_property::<Vector3<f32>>("v:position") {
+    pub fn write_obj<W : io::Write>(&self, output : W) -> Result<(),MeshLoadingError> {
+        let position = match self.coords_reader() {
             Some(prop) => prop,
             None => return Err(MeshLoadingError::MissingPosition),
         };
-        // let posprop = self.properties.get_vertex_property::<Vector3<f32>>("v:position").unwrap();
-        for v in self.topology.vertices() {
-            let pos = self.properties[(posprop, v)];
-            obj_data.vertices.push((pos.x,pos.y,pos.z,1.));
+        let double = self.has_double_positions();
+        let texprop = self.properties.get_halfedge_property::<Vector3<f32>>("h:texcoord");
+        let nprop = self.properties.get_halfedge_property::<Vector3<f32>>("h:normal");
+        let texset = self.properties.get_halfedge_property::<bool>("h:has_texcoord");
+        let nset = self.properties.get_halfedge_property::<bool>("h:has_normal");
+        let oprop = self.properties.get_face_property::<String>("f:object");
+        let gprop = self.properties.get_face_property::<String>("f:group");
+        let mut output = BufWriter::new(output);
+
+        let mut vindex : Vec<usize> = vec![0; self.topology.vertices_size()];
+        for (i,v) in self.topology.vertices().enumerate() {
+            vindex[v.idx()] = i;
+            let pos = position(v);
+            if double {
+                try!(writeln!(output,"v {} {} {} 1",pos[0],pos[1],pos[2]));
+            } else {
+                try!(writeln!(output,"v {} {} {} 1",pos[0] as f32,pos[1] as f32,pos[2] as f32));
+            }
         }
-        let mut obj = Object {
-            name : String::from(""),
-            primitives : Vec::new(),
+
+        let mut texcoords : Vec<Vector3<f32>> = Vec::new();
+        let mut normals : Vec<Vector3<f32>> = Vec::new();
+        let mut texindex : HashMap<(u32,u32,u32),usize> = HashMap::new();
+        let mut nindex : HashMap<(u32,u32,u32),usize> = HashMap::new();
+        let index = |x : Vector3<f32>, values : &mut Vec<Vector3<f32>>, indices : &mut HashMap<(u32,u32,u32),usize>| {
+            let len = values.len();
+            let idx = *indices.entry((x.x.to_bits(),x.y.to_bits(),x.z.to_bits())).or_insert(len);
+            if idx == len {
+                values.push(x);
+            }
+            idx
         };
+        let mut faces = Vec::new();
         for f in self.topology.faces() {
             let mut findex : Vec<(usize,Option<usize>,Option<usize>)> = Vec::new();
-            for v in self.topology.vertices_around(f) {
-                findex.push((v.idx(),None,None));
+            for h in self.topology.halfedges_around(f) {
+                let v = self.topology.to_vertex(h);
+                let has = |set : Option<PropertyHalfedge<bool>>| match set {
+                    Some(prop) => self.properties[(prop,h)],
+                    None => true,
+                };
+                let t = match texprop {
+                    Some(prop) if has(texset) => Some(index(self.properties[(prop,h)],&mut texcoords,&mut texindex)),
+                    _ => None,
+                };
+                let n = match nprop {
+                    Some(prop) if has(nset) => Some(index(self.properties[(prop,h)],&mut normals,&mut nindex)),
+                    _ => None,
+                };
+                findex.push((vindex[v.idx()],t,n));
             }
-            obj.primitives.push(f.idx());
-            obj_data.faces.push(findex);
+            faces.push((f,findex));
+        }
+
+        for t in texcoords.iter() {
+            try!(writeln!(output,"vt {} {} {}",t.x,t.y,t.z));
         }
-        obj_data.objects = vec![obj];
-        try!(obj_data.write(output));
+        for n in normals.iter() {
+            try!(writeln!(output,"vn {} {} {}",n.x,n.y,n.z));
+        }
+        // the faces are written in the order of their handles, so that they keep them when the file is loaded
+        let mut object = "";
+        let mut group = "";
+        for &(f,ref findex) in faces.iter() {
+            if let Some(prop) = oprop {
+                let o = self.properties[(prop,f)].as_str();
+                if o != object {
+                    if o.is_empty() {
+                        try!(writeln!(output,"o"));
+                    } else {
+                        try!(writeln!(output,"o {}",o));
+                    }
+                    object = o;
+                }
+            }
+            if let Some(prop) = gprop {
+                let g = self.properties[(prop,f)].as_str();
+                if g != group {
+                    if g.is_empty() {
+                        try!(writeln!(output,"g"));
+                    } else {
+                        try!(writeln!(output,"g {}",g));
+                    }
+                    group = g;
+                }
+            }
+            try!(write!(output,"f"));
+            for &(v,t,n) in findex.iter() {
+                try!(write!(output," {}/",v+1));
+                if let Some(t) = t {
+                    try!(write!(output,"{}",t+1));
+                }
+                try!(write!(output,"/"));
+                if let Some(n) = n {
+                    try!(write!(output,"{}",n+1));
+                }
+            }
+            try!(writeln!(output,""));
+        }
+        try!(output.flush());
         return Ok(());
     }
 }
@@ -163,7 +451,8 @@ mod tests {
     use mesh::Mesh;
     use std::io::ErrorKind;
     use std::io::BufWriter;
-    use handle::Vertex;
+    use handle::{Vertex, Face};
+    use mesh_iterator::HalfedgesAround;
     use nalgebra::Vector3;
     use std::str;
 
@@ -266,4 +555,240 @@ f 3// 2// 4//
         let m = Mesh::load("cube.obj").ok().unwrap();
         assert!(m.write("cube2.obj").is_ok());
     }
+
+    #[test]
+    fn load_write_ply() {
+        let m = Mesh::load("cube.obj").ok().unwrap();
+        assert!(m.write("cube2.ply").is_ok());
+        let m = Mesh::load("cube2.ply").ok().unwrap();
+        assert_eq!(m.topology.n_faces(),12);
+        assert_eq!(m.topology.n_vertices(),8);
+    }
+
+    #[test]
+    fn load_write_stl() {
+        let m = Mesh::load("cube.obj").ok().unwrap();
+        assert!(m.write("cube2.stl").is_ok());
+        let m = Mesh::load("cube2.stl").ok().unwrap();
+        assert_eq!(m.topology.n_faces(),12);
+        assert_eq!(m.topology.n_vertices(),8);
+    }
+
+    #[test]
+    fn load_write_off() {
+        let m = Mesh::load("cube.obj").ok().unwrap();
+        assert!(m.write("cube2.off").is_ok());
+        let m = Mesh::load("cube2.off").ok().unwrap();
+        assert_eq!(m.topology.n_faces(),12);
+        assert_eq!(m.topology.n_vertices(),8);
+    }
+
+    #[test]
+    fn load_write_f64() {
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f64;3]>().unwrap();
+        let vvec = m.add_vertices(3);
+        m.properties[(pos,vvec[0])] = [0.1,1e-300,1e9 + 0.5];
+        m.properties[(pos,vvec[1])] = [1. + 1e-12,0.,0.];
+        m.properties[(pos,vvec[2])] = [0.,1. / 3.,0.];
+        m.add_face(&vvec).unwrap();
+        for filename in &["f64.obj","f64.ply","f64.off"] {
+            assert!(m.write(filename).is_ok());
+            let m2 = Mesh::load_as::<[f64;3],_>(filename).ok().unwrap();
+            let pos2 = m2.positions::<[f64;3]>().unwrap();
+            for &v in vvec.iter() {
+                assert_eq!(m.properties[(pos,v)],m2.properties[(pos2,v)]);
+            }
+        }
+    }
+
+    #[test]
+    fn load_invalid_vertex_index() {
+        let input = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n";
+        match Mesh::load_obj(input.as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn load_obj_from_path() {
+        let path = Path::new(".").join("cube.obj");
+        let res = Mesh::load(&path);
+        assert!(res.is_ok());
+        let res = Mesh::load(path.to_str().unwrap().to_string());
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn load_obj_from_read() {
+        let input = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nf 1 2 3\nf 3 2 4\n";
+        let m = Mesh::load_obj(input.as_bytes()).ok().unwrap();
+        assert_eq!(m.topology.n_vertices(),4);
+        assert_eq!(m.topology.n_faces(),2);
+
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_obj(&mut output).is_ok());
+        let m = Mesh::load_obj(&output[..]).ok().unwrap();
+        assert_eq!(m.topology.n_vertices(),4);
+        assert_eq!(m.topology.n_faces(),2);
+    }
+
+    #[test]
+    fn load_write_obj_attributes() {
+        let input = r#"v 0 0 0
+v 1 0 0
+v 0 1 0
+v 1 1 0
+v 2 1 0
+vt 0 0
+vt 1 0
+vt 0 1
+vt 1 1
+vt 0.5 0.5
+vn 0 0 1
+o first
+f 1/1/1 2/2/1 3/3/1
+o second
+f 3/5/1 2/2/1 4/4/1
+o first
+f 4/4/1 2/2/1 5/1/1
+"#;
+        let m = Mesh::load_obj(input.as_bytes()).ok().unwrap();
+        let texprop = m.properties.get_halfedge_property::<Vector3<f32>>("h:texcoord").unwrap();
+        let nprop = m.properties.get_halfedge_property::<Vector3<f32>>("h:normal").unwrap();
+        let oprop = m.properties.get_face_property::<String>("f:object").unwrap();
+        let v2 = Vertex::new(2);
+        let f0 = Face::new(0);
+        let f1 = Face::new(1);
+        for h in m.topology.halfedges_around(f0) {
+            assert_eq!(m.properties[(nprop,h)],Vector3::new(0.,0.,1.));
+            if m.topology.to_vertex(h) == v2 {
+                assert_eq!(m.properties[(texprop,h)],Vector3::new(0.,1.,0.));
+            }
+        }
+        for h in m.topology.halfedges_around(f1) {
+            if m.topology.to_vertex(h) == v2 {
+                assert_eq!(m.properties[(texprop,h)],Vector3::new(0.5,0.5,0.));
+            }
+        }
+        assert_eq!(m.properties[(oprop,f0)],"first");
+        assert_eq!(m.properties[(oprop,f1)],"second");
+        assert_eq!(m.properties[(oprop,Face::new(2))],"first");
+
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_obj(&mut output).is_ok());
+        let m2 = Mesh::load_obj(&output[..]).ok().unwrap();
+        let texprop2 = m2.properties.get_halfedge_property::<Vector3<f32>>("h:texcoord").unwrap();
+        let oprop2 = m2.properties.get_face_property::<String>("f:object").unwrap();
+        for f in m.topology.faces() {
+            assert_eq!(m.properties[(oprop,f)],m2.properties[(oprop2,f)]);
+            for h in m.topology.halfedges_around(f) {
+                assert_eq!(m.properties[(texprop,h)],m2.properties[(texprop2,h)]);
+            }
+        }
+    }
+
+    #[test]
+    fn load_write_obj_mixed_attributes() {
+        let input = r#"v 0 0 0
+v 1 0 0
+v 0 1 0
+v 1 1 0
+vt 0 0
+vt 1 0
+vt 0 1
+f 1/1 2/2 3/3
+f 3 2 4
+"#;
+        let m = Mesh::load_obj(input.as_bytes()).ok().unwrap();
+        assert!(m.properties.get_halfedge_property::<Vector3<f32>>("h:normal").is_none());
+        let texset = m.properties.get_halfedge_property::<bool>("h:has_texcoord").unwrap();
+        for h in m.topology.halfedges_around(Face::new(0)) {
+            assert!(m.properties[(texset,h)]);
+        }
+        for h in m.topology.halfedges_around(Face::new(1)) {
+            assert!(!m.properties[(texset,h)]);
+        }
+
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_obj(&mut output).is_ok());
+        let text = str::from_utf8(&output).unwrap();
+        assert!(text.contains("f 1/1/ 2/2/ 3/3/\n"));
+        assert!(text.contains("f 3// 2// 4//\n"));
+        let m2 = Mesh::load_obj(&output[..]).ok().unwrap();
+        let texset2 = m2.properties.get_halfedge_property::<bool>("h:has_texcoord").unwrap();
+        for h in m.topology.halfedges() {
+            assert_eq!(m.properties[(texset,h)],m2.properties[(texset2,h)]);
+        }
+    }
+
+    #[test]
+    fn load_write_obj_groups() {
+        let input = r#"v 0 0 0
+v 1 0 0
+v 0 1 0
+v 1 1 0
+v 2 1 0
+o first
+g wall brick
+f 1 2 3
+g floor
+f 3 2 4
+o second
+f 4 2 5
+"#;
+        let m = Mesh::load_obj(input.as_bytes()).ok().unwrap();
+        let oprop = m.properties.get_face_property::<String>("f:object").unwrap();
+        let gprop = m.properties.get_face_property::<String>("f:group").unwrap();
+        let groups : Vec<&str> = m.topology.faces().map(|f| m.properties[(gprop,f)].as_str()).collect();
+        assert_eq!(groups,vec!["wall brick","floor","floor"]);
+
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_obj(&mut output).is_ok());
+        let m2 = Mesh::load_obj(&output[..]).ok().unwrap();
+        let oprop2 = m2.properties.get_face_property::<String>("f:object").unwrap();
+        let gprop2 = m2.properties.get_face_property::<String>("f:group").unwrap();
+        for f in m.topology.faces() {
+            assert_eq!(m.properties[(oprop,f)],m2.properties[(oprop2,f)]);
+            assert_eq!(m.properties[(gprop,f)],m2.properties[(gprop2,f)]);
+        }
+        assert!(Mesh::load_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".as_bytes()).ok().unwrap()
+            .properties.get_face_property::<String>("f:group").is_none());
+    }
+
+    #[test]
+    fn load_invalid_texcoord_index() {
+        let input = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nf 1/1 2/1 3/2\n";
+        match Mesh::load_obj(input.as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn load_write_obj_f64() {
+        let input = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+        let m = Mesh::load_obj_as::<Vector3<f64>,_>(input.as_bytes()).ok().unwrap();
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_obj(&mut output).is_ok());
+        let m = Mesh::load_obj_as::<Vector3<f64>,_>(&output[..]).ok().unwrap();
+        let pos = m.positions::<Vector3<f64>>().unwrap();
+        assert_eq!(m.properties[(pos,Vertex::new(2))],Vector3::new(0.,1.,0.));
+
+        // the f64 coordinates are written and read back without loss
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f64;3]>().unwrap();
+        let vvec = m.add_vertices(3);
+        m.properties[(pos,vvec[1])] = [1. + 1e-12,0.1,0.];
+        m.properties[(pos,vvec[2])] = [0.,1.,1. / 3.];
+        m.add_face(&vvec).unwrap();
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_obj(&mut output).is_ok());
+        let m2 = Mesh::load_obj_as::<[f64;3],_>(&output[..]).ok().unwrap();
+        let pos2 = m2.positions::<[f64;3]>().unwrap();
+        for &v in vvec.iter() {
+            assert_eq!(m.properties[(pos,v)],m2.properties[(pos2,v)]);
+        }
+    }
 }
@@ -1,199 +1,291 @@
 use mesh::*;
 use handle::*;
+use std::iter::FusedIterator;
+use std::collections::{HashSet, VecDeque};
+
+/// Implements `Iterator`, `DoubleEndedIterator`, `ExactSizeIterator` and `FusedIterator` for an
+/// iterator over the elements of a `Topology`, skipping the deleted ones.
+///
+/// The iterator yields the indices in `[front_,back_)` and `remaining_` counts the non deleted
+/// elements among them. When there is no deleted element, `nth` is a constant time jump.
+macro_rules! element_iterator {
+    ($name:ident, $handle:ident, $is_deleted:ident) => {
+        pub struct $name<'a> {
+            topology_ : &'a Topology,
+            front_ : usize,
+            back_ : usize,
+            remaining_ : usize,
+        }
+
+        impl<'a> Iterator for $name<'a> {
+            type Item = $handle;
+
+            fn next(&mut self) -> Option<$handle> {
+                while self.front_ < self.back_ {
+                    let x = $handle::new(self.front_);
+                    self.front_ += 1;
+                    if !self.topology_.$is_deleted(x) {
+                        self.remaining_ -= 1;
+                        return Some(x);
+                    }
+                }
+                None
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.remaining_, Some(self.remaining_))
+            }
+
+            fn count(self) -> usize {
+                self.remaining_
+            }
+
+            fn nth(&mut self, n : usize) -> Option<$handle> {
+                if self.remaining_ == self.back_ - self.front_ {
+                    // no deleted element left, jump directly
+                    let n = if n < self.remaining_ {n} else {self.remaining_};
+                    self.front_ += n;
+                    self.remaining_ -= n;
+                    return self.next();
+                }
+                for _ in 0..n {
+                    if self.next().is_none() {
+                        return None;
+                    }
+                }
+                self.next()
+            }
+        }
+
+        impl<'a> DoubleEndedIterator for $name<'a> {
+            fn next_back(&mut self) -> Option<$handle> {
+                while self.front_ < self.back_ {
+                    self.back_ -= 1;
+                    let x = $handle::new(self.back_);
+                    if !self.topology_.$is_deleted(x) {
+                        self.remaining_ -= 1;
+                        return Some(x);
+                    }
+                }
+                None
+            }
+        }
 
-pub struct VertexIterator<'a> {
-    topology_ : &'a Topology,
-    curr_ : Vertex,
-}
-
-impl<'a> Iterator for VertexIterator<'a> {
-    type Item = Vertex;
+        impl<'a> ExactSizeIterator for $name<'a> { }
 
-    fn next(&mut self) -> Option<Vertex> {
-        let v = self.curr_;
-        self.curr_ = Vertex::new(v.idx()+1);
-        if self.topology_.n_vertices() <= v.idx() {
-            return None;
-        } else {
-            return Some(v);
-        }
+        impl<'a> FusedIterator for $name<'a> { }
     }
 }
 
-pub struct FaceIterator<'a> {
+element_iterator!(VertexIterator, Vertex, is_deleted_vertex);
+element_iterator!(FaceIterator, Face, is_deleted_face);
+element_iterator!(EdgeIterator, Edge, is_deleted_edge);
+element_iterator!(HalfedgeIterator, Halfedge, is_deleted_halfedge);
+
+/// The walk along the halfedges shared by the circulators.
+///
+/// Around a `Vertex` the walk visits the outgoing halfedges, rotating clockwise unless
+/// `reversed_` is set. Around a `Face` it follows the next halfedges, counter-clockwise
+/// unless `reversed_` is set. The halfedges for which `skip_` returns `true` are not yielded.
+struct Circulation<'a> {
     topology_ : &'a Topology,
-    curr_ : Face,
+    end_ : Option<Halfedge>,
+    curr_ : Option<Halfedge>,
+    active_ : bool,
+    around_vertex_ : bool,
+    reversed_ : bool,
+    skip_ : fn(&Topology, Halfedge) -> bool,
 }
 
-impl<'a> Iterator for FaceIterator<'a> {
-    type Item = Face;
-
-    fn next(&mut self) -> Option<Face> {
-        let f = self.curr_;
-        self.curr_ = Face::new(f.idx()+1);
-        if self.topology_.n_faces() <= f.idx() {
-            return None;
-        } else {
-            return Some(f);
+impl<'a> Circulation<'a> {
+    fn new(topology : &'a Topology, start : Option<Halfedge>, around_vertex : bool, skip : fn(&Topology, Halfedge) -> bool) -> Circulation<'a> {
+        Circulation {
+            topology_ : topology,
+            end_ : start,
+            curr_ : start,
+            active_ : false,
+            around_vertex_ : around_vertex,
+            reversed_ : false,
+            skip_ : skip,
         }
     }
-}
-
-pub struct EdgeIterator<'a> {
-    topology_ : &'a Topology,
-    curr_ : Edge,
-}
 
-impl<'a> Iterator for EdgeIterator<'a> {
-    type Item = Edge;
+    fn start_at(&mut self, h : Halfedge) {
+        let same_element = match self.end_ {
+            Some(start) if self.around_vertex_ => self.topology_.from_vertex(h) == self.topology_.from_vertex(start),
+            Some(start) => self.topology_.face(h) == self.topology_.face(start),
+            None => false,
+        };
+        assert!(same_element, "the halfedge {} is not part of the rotation",h);
+        self.end_ = Some(h);
+        self.curr_ = Some(h);
+        self.active_ = false;
+    }
 
-    fn next(&mut self) -> Option<Edge> {
-        let e = self.curr_;
-        self.curr_ = Edge::new(e.idx()+1);
-        if self.topology_.n_edges() <= e.idx() {
-            return None;
-        } else {
-            return Some(e);
+    fn step(&self, h : Halfedge) -> Halfedge {
+        match (self.around_vertex_,self.reversed_) {
+            (true,false) => self.topology_.cw_rotated_halfedge(h),
+            (true,true) => self.topology_.ccw_rotated_halfedge(h),
+            (false,false) => self.topology_.next_halfedge(h),
+            (false,true) => self.topology_.prev_halfedge(h),
         }
     }
-}
-
-pub struct HalfedgeIterator<'a> {
-    topology_ : &'a Topology,
-    curr_ : Halfedge,
-}
-
-impl<'a> Iterator for HalfedgeIterator<'a> {
-    type Item = Halfedge;
 
     fn next(&mut self) -> Option<Halfedge> {
-        let h = self.curr_;
-        self.curr_ = Halfedge::new(h.idx()+1);
-        if self.topology_.n_halfedges() <= h.idx() {
-            return None;
-        } else {
-            return Some(h);
+        loop {
+            let h = match self.curr_ {
+                Some(x) => x,
+                None => return None,
+            };
+            if self.active_ && self.curr_ == self.end_ {
+                return None;
+            }
+            self.active_ = true;
+            self.curr_ = Some(self.step(h));
+            if !(self.skip_)(self.topology_,h) {
+                return Some(h);
+            }
         }
     }
 }
 
-pub struct VerticesAroundVertexCirculator<'a> {
-    topology_ : &'a Topology,
-    end_ : Option<Halfedge>,
-    curr_ : Option<Halfedge>,
-    active_ : bool
+fn skip_none(_ : &Topology, _ : Halfedge) -> bool {
+    false
 }
 
-impl<'a> Iterator for VerticesAroundVertexCirculator<'a> {
-    type Item = Vertex;
-
-    fn next(&mut self) -> Option<Vertex> {
-        if self.curr_.is_none() {
-            return None;
-        }
-        if self.active_ && self.curr_ == self.end_ {
-            return None;
-        }
-        self.active_ = true;
-        let v = self.topology_.to_vertex(self.curr_.unwrap());
-        self.curr_ = Some(self.topology_.cw_rotated_halfedge(self.curr_.unwrap()));
-        return Some(v);
-    }
+fn skip_boundary(topology : &Topology, h : Halfedge) -> bool {
+    topology.is_boundary_halfedge(h)
 }
 
-pub struct HalfedgesAroundVertexCirculator<'a> {
-    topology_ : &'a Topology,
-    end_ : Option<Halfedge>,
-    curr_ : Option<Halfedge>,
-    active_ : bool
+fn skip_opposite_boundary(topology : &Topology, h : Halfedge) -> bool {
+    topology.is_boundary_halfedge(topology.opposite_halfedge(h))
 }
 
-impl<'a> Iterator for HalfedgesAroundVertexCirculator<'a> {
-    type Item = Halfedge;
-
-    fn next(&mut self) -> Option<Halfedge> {
-        if self.curr_.is_none() {
-            return None;
+/// Defines a circulator yielding `$map` for each halfedge `$h` of the `Circulation`.
+macro_rules! circulator {
+    ($name:ident, $item:ident, |$topology:ident, $h:ident| $map:expr) => {
+        pub struct $name<'a> {
+            circulation_ : Circulation<'a>,
+        }
+
+        impl<'a> $name<'a> {
+            /// Starts the rotation at the `Halfedge` `h` instead of the default one.
+            ///
+            /// Around a `Vertex`, `h` must be an outgoing halfedge of the vertex. Around a `Face`,
+            /// `h` must be a halfedge of the face. The rotation must not have started yet.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `h` is not part of the rotation.
+            pub fn starting_at(mut self, h : Halfedge) -> $name<'a> {
+                self.circulation_.start_at(h);
+                self
+            }
+
+            /// Pairs each element with its value in the property `p`.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use lwmesh::*;
+            ///
+            /// let mut m = Mesh::new();
+            /// let vvec = m.add_vertices(3);
+            /// m.add_face(&vvec).unwrap();
+            /// let prop = m.properties.add_vertex_property::<u32>("v:my_prop",0).unwrap();
+            /// m.properties[(prop,vvec[1])] = 1;
+            /// m.properties[(prop,vvec[2])] = 2;
+            ///
+            /// let sum : u32 = m.topology.vertices_around(vvec[0]).with_property(&m.properties,prop).map(|(_,x)| x).sum();
+            /// assert_eq!(sum,3);
+            /// ```
+            pub fn with_property<T : PropertyElement, D : 'static>(self, properties : &'a Properties, p : PropertyHandle<(T,D)>) -> PropertyCirculator<'a,$name<'a>,D>
+                where $name<'a> : Iterator<Item = Handle<T>> {
+                PropertyCirculator {
+                    circulator_ : self,
+                    data_ : properties.property_slice(p),
+                }
+            }
         }
-        if self.active_ && self.curr_ == self.end_ {
-            return None;
+
+        impl<'a> Iterator for $name<'a> {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<$item> {
+                match self.circulation_.next() {
+                    Some($h) => {
+                        #[allow(unused_variables)]
+                        let $topology = self.circulation_.topology_;
+                        Some($map)
+                    },
+                    None => None,
+                }
+            }
         }
-        self.active_ = true;
-        let h = self.curr_.unwrap();
-        self.curr_ = Some(self.topology_.cw_rotated_halfedge(self.curr_.unwrap()));
-        return Some(h);
     }
 }
 
-pub struct FacesAroundVertexCirculator<'a> {
-    topology_ : &'a Topology,
-    end_ : Option<Halfedge>,
-    curr_ : Option<Halfedge>,
-    active_ : bool
-}
-
-impl<'a> Iterator for FacesAroundVertexCirculator<'a> {
-    type Item = Face;
+/// Defines a circulator around a `Vertex`, which rotates clockwise unless reversed by `ccw`.
+macro_rules! vertex_circulator {
+    ($name:ident, $item:ident, |$topology:ident, $h:ident| $map:expr) => {
+        circulator!($name, $item, |$topology, $h| $map);
 
-    fn next(&mut self) -> Option<Face> {
-        if self.curr_.is_none() {
-            return None;
-        }
-        if self.active_ && self.curr_ == self.end_ {
-            return None;
+        impl<'a> $name<'a> {
+            /// Rotates counter-clockwise around the vertex instead of clockwise.
+            /// The rotation must not have started yet.
+            pub fn ccw(mut self) -> $name<'a> {
+                self.circulation_.reversed_ = true;
+                self
+            }
         }
-        self.active_ = true;
-        let f = self.topology_.face(self.curr_.unwrap()).unwrap();
-        loop {
-            self.curr_ = Some(self.topology_.cw_rotated_halfedge(self.curr_.unwrap()));
-            if !self.topology_.is_boundary_halfedge(self.curr_.unwrap()) {break;}
-        }
-        return Some(f);
     }
 }
 
-pub struct VerticesAroundFaceCirculator<'a> {
-    topology_ : &'a Topology,
-    end_ : Halfedge,
-    curr_ : Halfedge,
-    active_ : bool
-}
+/// Defines a circulator around a `Face`, which rotates counter-clockwise unless reversed by `cw`.
+macro_rules! face_circulator {
+    ($name:ident, $item:ident, |$topology:ident, $h:ident| $map:expr) => {
+        circulator!($name, $item, |$topology, $h| $map);
 
-impl<'a> Iterator for VerticesAroundFaceCirculator<'a> {
-    type Item = Vertex;
-
-    fn next(&mut self) -> Option<Vertex> {
-        if self.active_ && self.curr_ == self.end_ {
-            return None;
+        impl<'a> $name<'a> {
+            /// Rotates clockwise around the face instead of counter-clockwise.
+            /// The rotation must not have started yet.
+            pub fn cw(mut self) -> $name<'a> {
+                self.circulation_.reversed_ = true;
+                self
+            }
         }
-        self.active_ = true;
-        let v = self.topology_.to_vertex(self.curr_);
-        self.curr_ = self.topology_.next_halfedge(self.curr_);
-        return Some(v);
     }
 }
 
-pub struct HalfedgesAroundFaceCirculator<'a> {
-    topology_ : &'a Topology,
-    end_ : Halfedge,
-    curr_ : Halfedge,
-    active_ : bool
+/// A circulator yielding the elements of another circulator paired with their value in a property.
+pub struct PropertyCirculator<'a, I, D : 'a> {
+    circulator_ : I,
+    data_ : &'a [D],
 }
 
-impl<'a> Iterator for HalfedgesAroundFaceCirculator<'a> {
-    type Item = Halfedge;
+impl<'a, T, I : Iterator<Item = Handle<T>>, D> Iterator for PropertyCirculator<'a, I, D> {
+    type Item = (Handle<T>, &'a D);
 
-    fn next(&mut self) -> Option<Halfedge> {
-        if self.active_ && self.curr_ == self.end_ {
-            return None;
+    fn next(&mut self) -> Option<(Handle<T>, &'a D)> {
+        match self.circulator_.next() {
+            Some(x) => {
+                let data = &self.data_[x.idx()];
+                Some((x,data))
+            },
+            None => None,
         }
-        self.active_ = true;
-        let h = self.curr_;
-        self.curr_ = self.topology_.next_halfedge(self.curr_);
-        return Some(h);
     }
 }
 
+vertex_circulator!(VerticesAroundVertexCirculator, Vertex, |topology, h| topology.to_vertex(h));
+vertex_circulator!(HalfedgesAroundVertexCirculator, Halfedge, |topology, h| h);
+vertex_circulator!(EdgesAroundVertexCirculator, Edge, |topology, h| topology.edge(h));
+vertex_circulator!(FacesAroundVertexCirculator, Face, |topology, h| topology.face(h).unwrap());
+face_circulator!(VerticesAroundFaceCirculator, Vertex, |topology, h| topology.to_vertex(h));
+face_circulator!(HalfedgesAroundFaceCirculator, Halfedge, |topology, h| h);
+face_circulator!(EdgesAroundFaceCirculator, Edge, |topology, h| topology.edge(h));
+face_circulator!(FacesAroundFaceCirculator, Face, |topology, h| topology.face(topology.opposite_halfedge(h)).unwrap());
+
 pub trait VerticesAround<'a,H,I> {
     fn vertices_around(&'a self, handle : H) -> I;
 }
@@ -218,11 +310,8 @@ impl<'a> VerticesAround<'a,Vertex,VerticesAroundVertexCirculator<'a> > for Topol
     /// }
     /// ```
     fn vertices_around(&'a self, v : Vertex) -> VerticesAroundVertexCirculator<'a> {
-        VerticesAroundVertexCirculator::<'a> {
-            topology_ : &self,
-            end_ : self.halfedge(v),
-            curr_ : self.halfedge(v),
-            active_ : false
+        VerticesAroundVertexCirculator {
+            circulation_ : Circulation::new(self,self.halfedge(v),true,skip_none),
         }
     }
 }
@@ -248,11 +337,8 @@ impl<'a> VerticesAround<'a,Face,VerticesAroundFaceCirculator<'a> > for Topology
     /// }
     /// ```
     fn vertices_around(&'a self, f : Face) -> VerticesAroundFaceCirculator<'a> {
-        VerticesAroundFaceCirculator::<'a> {
-            topology_ : &self,
-            end_ : self.face_halfedge(f),
-            curr_ : self.face_halfedge(f),
-            active_ : false
+        VerticesAroundFaceCirculator {
+            circulation_ : Circulation::new(self,Some(self.face_halfedge(f)),false,skip_none),
         }
     }
 }
@@ -283,10 +369,7 @@ impl<'a> HalfedgesAround<'a,Vertex,HalfedgesAroundVertexCirculator<'a> > for Top
     /// ```
     fn halfedges_around(&self, v : Vertex) -> HalfedgesAroundVertexCirculator {
         HalfedgesAroundVertexCirculator {
-            topology_ : &self,
-            end_ : self.halfedge(v),
-            curr_ : self.halfedge(v),
-            active_ : false
+            circulation_ : Circulation::new(self,self.halfedge(v),true,skip_none),
         }
     }
 }
@@ -312,10 +395,7 @@ impl<'a> HalfedgesAround<'a,Face,HalfedgesAroundFaceCirculator<'a> > for Topolog
     /// ```
     fn halfedges_around(&self, f : Face) -> HalfedgesAroundFaceCirculator {
         HalfedgesAroundFaceCirculator {
-            topology_ : &self,
-            end_ : self.face_halfedge(f),
-            curr_ : self.face_halfedge(f),
-            active_ : false
+            circulation_ : Circulation::new(self,Some(self.face_halfedge(f)),false,skip_none),
         }
     }
 }
@@ -344,30 +424,155 @@ impl<'a> FacesAround<'a,Vertex,FacesAroundVertexCirculator<'a> > for Topology {
     /// }
     /// ```
     fn faces_around(&self, v : Vertex) -> FacesAroundVertexCirculator {
-        match self.halfedge(v) {
-            None => FacesAroundVertexCirculator {
-                topology_ : &self,
-                end_ : None,
-                curr_ : None,
-                active_ : false
-            },
-            Some(x) => {
-                let mut h = x;
-                while self.is_boundary_halfedge(h) {
-                    h = self.cw_rotated_halfedge(h);
-                }
-                FacesAroundVertexCirculator {
-                    topology_ : &self,
-                    end_ : Some(h),
-                    curr_ : Some(h),
-                    active_ : false
+        FacesAroundVertexCirculator {
+            circulation_ : Circulation::new(self,self.halfedge(v),true,skip_boundary),
+        }
+    }
+}
+
+impl<'a> FacesAround<'a,Face,FacesAroundFaceCirculator<'a> > for Topology {
+    /// Iterator over the faces sharing an edge with a face in the `Mesh`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// let f0 = m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+    /// let f1 = m.add_face(&vec![vvec[0],vvec[2],vvec[3]]).unwrap();
+    ///
+    /// let faces : Vec<Face> = m.topology.faces_around(f0).collect();
+    /// assert!(faces == vec![f1]);
+    /// ```
+    fn faces_around(&self, f : Face) -> FacesAroundFaceCirculator {
+        FacesAroundFaceCirculator {
+            circulation_ : Circulation::new(self,Some(self.face_halfedge(f)),false,skip_opposite_boundary),
+        }
+    }
+}
+
+pub trait EdgesAround<'a,H,I> {
+    fn edges_around(&'a self, handle : H) -> I;
+}
+
+impl<'a> EdgesAround<'a,Vertex,EdgesAroundVertexCirculator<'a> > for Topology {
+    /// Iterator over the edges around a vertex in the `Mesh`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///    vvec.push(m.add_vertex());
+    /// }
+    /// m.add_face(&vvec);
+    ///
+    /// for e in m.topology.edges_around(vvec[0]) {
+    ///     println!("e{}",e.idx());
+    /// }
+    /// ```
+    fn edges_around(&self, v : Vertex) -> EdgesAroundVertexCirculator {
+        EdgesAroundVertexCirculator {
+            circulation_ : Circulation::new(self,self.halfedge(v),true,skip_none),
+        }
+    }
+}
+
+impl<'a> EdgesAround<'a,Face,EdgesAroundFaceCirculator<'a> > for Topology {
+    /// Iterator over the edges of a face in the `Mesh`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let mut vvec = Vec::<Vertex>::new();
+    /// for _ in 0..3 {
+    ///    vvec.push(m.add_vertex());
+    /// }
+    /// let f = m.add_face(&vvec).unwrap();
+    ///
+    /// for e in m.topology.edges_around(f) {
+    ///     println!("e{}",e.idx());
+    /// }
+    /// ```
+    fn edges_around(&self, f : Face) -> EdgesAroundFaceCirculator {
+        EdgesAroundFaceCirculator {
+            circulation_ : Circulation::new(self,Some(self.face_halfedge(f)),false,skip_none),
+        }
+    }
+}
+
+/// Iterator over the vertices at most `k` edges away from a vertex, in breadth first order.
+pub struct VerticesInRing<'a> {
+    topology_ : &'a Topology,
+    queue_ : VecDeque<(Vertex,usize)>,
+    visited_ : HashSet<Vertex>,
+    k_ : usize,
+}
+
+impl<'a> Iterator for VerticesInRing<'a> {
+    type Item = Vertex;
+
+    fn next(&mut self) -> Option<Vertex> {
+        let (v,depth) = match self.queue_.pop_front() {
+            Some(x) => x,
+            None => return None,
+        };
+        if depth < self.k_ {
+            for w in self.topology_.vertices_around(v) {
+                if self.visited_.insert(w) {
+                    self.queue_.push_back((w,depth+1));
                 }
-            },
+            }
         }
+        return Some(v);
     }
 }
 
 impl Topology {
+    /// Iterator over the vertices of the `k`-ring of `v`, that is the vertices at most `k` edges
+    /// away from `v`, excluding `v` itself.
+    ///
+    /// The vertices are yielded ring by ring: first the one-ring, then the vertices two edges away, etc.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+    /// m.add_face(&vec![vvec[1],vvec[3],vvec[2]]).unwrap();
+    ///
+    /// assert_eq!(m.topology.vertices_in_ring(vvec[0],1).count(),2);
+    /// assert_eq!(m.topology.vertices_in_ring(vvec[0],2).count(),3);
+    /// ```
+    pub fn vertices_in_ring(&self, v : Vertex, k : usize) -> VerticesInRing {
+        let mut ring = VerticesInRing {
+            topology_ : &self,
+            queue_ : VecDeque::new(),
+            visited_ : HashSet::new(),
+            k_ : k,
+        };
+        ring.visited_.insert(v);
+        if k > 0 {
+            for w in self.vertices_around(v) {
+                if ring.visited_.insert(w) {
+                    ring.queue_.push_back((w,1));
+                }
+            }
+        }
+        ring
+    }
+
     /// Iterator over the vertices in the `Mesh`
     ///
     /// # Examples
@@ -387,7 +592,9 @@ impl Topology {
     pub fn vertices(&self) -> VertexIterator {
         VertexIterator {
             topology_ : &self,
-            curr_ : Vertex::new(0),
+            front_ : 0,
+            back_ : self.vertices_size(),
+            remaining_ : self.n_vertices(),
         }
     }
 
@@ -412,7 +619,9 @@ impl Topology {
     pub fn faces(&self) -> FaceIterator {
         FaceIterator {
             topology_ : &self,
-            curr_ : Face::new(0),
+            front_ : 0,
+            back_ : self.faces_size(),
+            remaining_ : self.n_faces(),
         }
     }
 
@@ -437,7 +646,9 @@ impl Topology {
     pub fn edges(&self) -> EdgeIterator {
         EdgeIterator {
             topology_ : &self,
-            curr_ : Edge::new(0),
+            front_ : 0,
+            back_ : self.edges_size(),
+            remaining_ : self.n_edges(),
         }
     }
 
@@ -462,18 +673,65 @@ impl Topology {
     pub fn halfedges(&self) -> HalfedgeIterator {
         HalfedgeIterator {
             topology_ : &self,
-            curr_ : Halfedge::new(0),
+            front_ : 0,
+            back_ : self.halfedges_size(),
+            remaining_ : self.n_halfedges(),
         }
     }
 }
 
+impl Mesh {
+    /// Computes a value for every `Vertex` from its value in the property `p` and the values of its
+    /// one-ring neighbors, returned along with the vertex.
+    ///
+    /// As the results are collected before being returned, they can be written back into `p`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pos = m.add_positions::<[f32;3]>().unwrap();
+    /// let vvec = m.add_vertices(3);
+    /// m.add_face(&vvec).unwrap();
+    /// m.properties[(pos,vvec[1])] = [1.,0.,0.];
+    /// m.properties[(pos,vvec[2])] = [0.,1.,0.];
+    ///
+    /// let smoothed = m.one_ring_map(pos,|_,_,ring| {
+    ///     let mut sum = [0f32;3];
+    ///     let mut n = 0.;
+    ///     for (_,p) in ring {
+    ///         for i in 0..3 {
+    ///             sum[i] += p[i];
+    ///         }
+    ///         n += 1.;
+    ///     }
+    ///     [sum[0] / n,sum[1] / n,sum[2] / n]
+    /// });
+    /// for (v,p) in smoothed {
+    ///     m.properties[(pos,v)] = p;
+    /// }
+    /// assert_eq!(m.properties[(pos,vvec[0])],[0.5,0.5,0.]);
+    /// ```
+    pub fn one_ring_map<D : 'static, R, F>(&self, p : PropertyVertex<D>, mut f : F) -> Vec<(Vertex,R)>
+        where F : FnMut(Vertex, &D, PropertyCirculator<VerticesAroundVertexCirculator,D>) -> R {
+        let data = self.properties.property_slice(p);
+        self.topology.vertices().map(|v| {
+            let ring = self.topology.vertices_around(v).with_property(&self.properties,p);
+            (v,f(v,&data[v.idx()],ring))
+        }).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use mesh::*;
-    use handle::Vertex;
+    use handle::{Vertex, Face, Edge, Halfedge};
     use mesh_iterator::VerticesAround;
     use mesh_iterator::HalfedgesAround;
     use mesh_iterator::FacesAround;
+    use mesh_iterator::EdgesAround;
 
     #[test]
     fn iterator_and_properties() {
@@ -569,4 +827,160 @@ mod tests {
         }
         assert_eq!(i,0);
     }
+
+    #[test]
+    fn element_iterators_exact_size() {
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(5);
+        m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+        m.add_face(&vec![vvec[0],vvec[2],vvec[3]]).unwrap();
+        m.add_face(&vec![vvec[0],vvec[3],vvec[4]]).unwrap();
+
+        let mut it = m.topology.vertices();
+        assert_eq!(it.len(),5);
+        assert!(it.next() == Some(vvec[0]));
+        assert!(it.next_back() == Some(vvec[4]));
+        assert_eq!(it.len(),3);
+        assert!(it.nth(1) == Some(vvec[2]));
+        assert_eq!(it.len(),1);
+        assert!(it.nth(3).is_none());
+        assert!(it.next().is_none());
+        assert!(it.next_back().is_none());
+        assert_eq!(m.topology.halfedges().len(),14);
+        assert_eq!(m.topology.edges().rev().next().unwrap().idx(),6);
+
+        m.delete_face(Face::new(1));
+        assert_eq!(m.topology.faces().len(),2);
+        let faces : Vec<Face> = m.topology.faces().rev().collect();
+        assert!(faces == vec![Face::new(2),Face::new(0)]);
+        assert!(m.topology.faces().nth(1) == Some(Face::new(2)));
+        assert!(m.topology.faces().skip(2).next().is_none());
+        let mut it = m.topology.edges();
+        assert_eq!(it.len(),m.topology.n_edges());
+        let n = it.len();
+        assert_eq!(it.by_ref().count(),n);
+    }
+
+    #[test]
+    fn rotation_direction_and_start() {
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(5);
+        let f0 = m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+        let f1 = m.add_face(&vec![vvec[0],vvec[2],vvec[3]]).unwrap();
+        let f2 = m.add_face(&vec![vvec[0],vvec[3],vvec[4]]).unwrap();
+
+        let cw : Vec<Vertex> = m.topology.vertices_around(vvec[0]).collect();
+        let ccw : Vec<Vertex> = m.topology.vertices_around(vvec[0]).ccw().collect();
+        assert_eq!(cw.len(),4);
+        assert!(ccw[0] == cw[0]);
+        assert!(ccw[1..].iter().rev().eq(cw[1..].iter()));
+
+        let h = m.topology.find_halfedge(vvec[0],vvec[2]).unwrap();
+        let from_h : Vec<Vertex> = m.topology.vertices_around(vvec[0]).starting_at(h).collect();
+        assert!(from_h[0] == vvec[2]);
+        assert_eq!(from_h.len(),4);
+        let from_h : Vec<Vertex> = m.topology.vertices_around(vvec[0]).ccw().starting_at(h).collect();
+        assert!(from_h == vec![vvec[2],vvec[3],vvec[4],vvec[1]]);
+
+        let faces : Vec<Face> = m.topology.faces_around(vvec[0]).ccw().starting_at(h).collect();
+        assert!(faces == vec![f1,f2,f0]);
+        let faces : Vec<Face> = m.topology.faces_around(vvec[0]).starting_at(h).collect();
+        assert!(faces == vec![f1,f0,f2]);
+
+        let edges : Vec<Edge> = m.topology.edges_around(vvec[0]).ccw().starting_at(h).collect();
+        let expected : Vec<Edge> = outgoing_edges(&m,vvec[0],&[vvec[2],vvec[3],vvec[4],vvec[1]]);
+        assert!(edges == expected);
+
+        let cw : Vec<Vertex> = m.topology.vertices_around(f1).cw().collect();
+        let ccw : Vec<Vertex> = m.topology.vertices_around(f1).collect();
+        assert!(ccw[0] == cw[0]);
+        assert!(ccw[1..].iter().rev().eq(cw[1..].iter()));
+        let h = m.topology.find_halfedge(vvec[2],vvec[3]).unwrap();
+        let from_h : Vec<Halfedge> = m.topology.halfedges_around(f1).cw().starting_at(h).collect();
+        assert_eq!(from_h.len(),3);
+        assert!(from_h[0] == h);
+        assert!(from_h[1] == m.topology.prev_halfedge(h));
+        assert_eq!(m.topology.edges_around(f1).count(),3);
+    }
+
+    fn outgoing_edges(m : &Mesh, v : Vertex, vs : &[Vertex]) -> Vec<Edge> {
+        vs.iter().map(|&w| m.topology.edge(m.topology.find_halfedge(v,w).unwrap())).collect()
+    }
+
+    #[test]
+    fn faces_around_face() {
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(5);
+        let f0 = m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+        let f1 = m.add_face(&vec![vvec[0],vvec[2],vvec[3]]).unwrap();
+        let f2 = m.add_face(&vec![vvec[0],vvec[3],vvec[4]]).unwrap();
+
+        assert!(m.topology.faces_around(f0).collect::<Vec<Face>>() == vec![f1]);
+        let mut faces : Vec<Face> = m.topology.faces_around(f1).collect();
+        faces.sort();
+        assert!(faces == vec![f0,f2]);
+
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(3);
+        let f = m.add_face(&vvec).unwrap();
+        assert_eq!(m.topology.faces_around(f).count(),0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn starting_at_other_vertex() {
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(3);
+        m.add_face(&vvec).unwrap();
+        let h = m.topology.find_halfedge(vvec[1],vvec[2]).unwrap();
+        m.topology.vertices_around(vvec[0]).starting_at(h);
+    }
+
+    #[test]
+    fn property_circulators() {
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(4);
+        let f0 = m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+        let f1 = m.add_face(&vec![vvec[1],vvec[3],vvec[2]]).unwrap();
+        let vprop = m.properties.add_vertex_property::<usize>("v:index",0).unwrap();
+        let fprop = m.properties.add_face_property::<String>("f:name",String::new()).unwrap();
+        for v in m.topology.vertices() {
+            m.properties[(vprop,v)] = 10 * v.idx();
+        }
+        m.properties[(fprop,f0)] = "f0".to_string();
+        m.properties[(fprop,f1)] = "f1".to_string();
+
+        for (v,x) in m.topology.vertices_around(vvec[1]).with_property(&m.properties,vprop) {
+            assert_eq!(*x,10 * v.idx());
+        }
+        let names : Vec<&String> = m.topology.faces_around(vvec[2]).ccw().with_property(&m.properties,fprop).map(|(_,x)| x).collect();
+        assert_eq!(names.len(),2);
+
+        let degrees = m.one_ring_map(vprop,|v,x,ring| {
+            assert_eq!(*x,10 * v.idx());
+            ring.count()
+        });
+        assert!(degrees == vec![(vvec[0],2),(vvec[1],3),(vvec[2],3),(vvec[3],2)]);
+    }
+
+    #[test]
+    fn vertices_in_ring() {
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(8);
+        for i in 0..3 {
+            let vs = vec![vvec[2*i],vvec[2*i+2],vvec[2*i+3],vvec[2*i+1]];
+            m.add_face(&vs).unwrap();
+        }
+
+        assert_eq!(m.topology.vertices_in_ring(vvec[0],0).count(),0);
+        let ring : Vec<Vertex> = m.topology.vertices_in_ring(vvec[0],1).collect();
+        assert_eq!(ring.len(),2);
+        assert!(ring.contains(&vvec[1]) && ring.contains(&vvec[2]));
+        let ring : Vec<Vertex> = m.topology.vertices_in_ring(vvec[0],2).collect();
+        assert_eq!(ring.len(),4);
+        assert!(ring[2..].contains(&vvec[3]) && ring[2..].contains(&vvec[4]));
+        assert_eq!(m.topology.vertices_in_ring(vvec[0],10).count(),7);
+        let isolated = m.add_vertex();
+        assert_eq!(m.topology.vertices_in_ring(isolated,2).count(),0);
+    }
 }
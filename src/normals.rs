@@ -0,0 +1,230 @@
+use mesh::*;
+use handle::*;
+use nalgebra::Vector3;
+use mesh_iterator::HalfedgesAround;
+use geometry::{sub, add, scale, cross, norm, normalized, angle};
+
+/// Weighting of the face normals used to compute the vertex normals.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NormalWeighting {
+    /// Every incident face contributes equally.
+    Uniform,
+    /// The incident faces are weighted by their area.
+    Area,
+    /// The incident faces are weighted by their angle at the vertex.
+    Angle,
+}
+
+/// Returns the angle between `a` and `b`, or zero if one of them is null.
+fn corner_angle(a : [f64;3], b : [f64;3]) -> f64 {
+    if norm(a) > 0. && norm(b) > 0. {
+        angle(normalized(a),normalized(b))
+    } else {
+        0.
+    }
+}
+
+/// Converts `n` to the `Vector3<f32>` stored in the normal properties.
+fn to_vector(n : [f64;3]) -> Vector3<f32> {
+    Vector3::new(n[0] as f32,n[1] as f32,n[2] as f32)
+}
+
+impl Mesh {
+    /// Returns the normal of the `Face` `f` scaled by twice its area, computed from the `position` reader.
+    ///
+    /// Non planar polygons are handled with Newell's method, relative to the first corner
+    /// so that the precision does not depend on the distance to the origin.
+    fn area_face_normal(&self, position : &Fn(Vertex) -> [f64;3], f : Face) -> [f64;3] {
+        let mut n = [0.,0.,0.];
+        let o = position(self.topology.to_vertex(self.topology.face_halfedge(f)));
+        for h in self.topology.halfedges_around(f) {
+            let p0 = sub(position(self.topology.from_vertex(h)),o);
+            let p1 = sub(position(self.topology.to_vertex(h)),o);
+            n = add(n,cross(p0,p1));
+        }
+        n
+    }
+
+    /// Compute the unit normal of every `Face` from the vertex positions and store it in `f:normal`.
+    ///
+    /// Returns `None` if the `Mesh` has no positions or if `f:normal` exists with another type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pos = m.add_positions::<[f32;3]>().unwrap();
+    /// let vvec = m.add_vertices(4);
+    /// m.properties[(pos,vvec[1])] = [1.,0.,0.];
+    /// m.properties[(pos,vvec[2])] = [0.,1.,0.];
+    /// m.properties[(pos,vvec[3])] = [0.,0.,1.];
+    /// for &(a,b,c) in &[(0,2,1),(0,1,3),(1,2,3),(0,3,2)] {
+    ///     m.add_face(&vec![vvec[a],vvec[b],vvec[c]]).unwrap();
+    /// }
+    /// let normals = m.compute_face_normals().unwrap();
+    /// for f in m.topology.faces() {
+    ///     assert!((m.properties[(normals,f)].norm() - 1.).abs() < 1e-6);
+    /// }
+    /// ```
+    pub fn compute_face_normals(&mut self) -> Option<PropertyFace<Vector3<f32>>> {
+        let fnormals : Vec<(Face,[f64;3])> = match self.coords_reader() {
+            Some(position) => self.topology.faces().map(|f| (f,normalized(self.area_face_normal(&*position,f)))).collect(),
+            None => return None,
+        };
+        let normals = match self.properties.get_face_property::<Vector3<f32>>("f:normal") {
+            Some(prop) => prop,
+            None => match self.properties.add_face_property::<Vector3<f32>>("f:normal",Vector3::new(0f32,0f32,0f32)) {
+                Some(prop) => prop,
+                None => return None,
+            },
+        };
+        for (f,n) in fnormals {
+            self.properties[(normals,f)] = to_vector(n);
+        }
+        Some(normals)
+    }
+
+    /// Compute the unit normal of every `Vertex` from the vertex positions and store it in `v:normal`.
+    ///
+    /// The vertex normal is the normalized sum of the normals of the incident faces,
+    /// weighted according to `weighting`. Isolated vertices get a null normal.
+    ///
+    /// Returns `None` if the `Mesh` has no positions or if `v:normal` exists with another type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let pos = m.add_positions::<[f32;3]>().unwrap();
+    /// let vvec = m.add_vertices(4);
+    /// m.properties[(pos,vvec[1])] = [1.,0.,0.];
+    /// m.properties[(pos,vvec[2])] = [0.,1.,0.];
+    /// m.properties[(pos,vvec[3])] = [0.,0.,1.];
+    /// for &(a,b,c) in &[(0,2,1),(0,1,3),(1,2,3),(0,3,2)] {
+    ///     m.add_face(&vec![vvec[a],vvec[b],vvec[c]]).unwrap();
+    /// }
+    /// let normals = m.compute_vertex_normals(NormalWeighting::Angle).unwrap();
+    /// for v in m.topology.vertices() {
+    ///     assert!((m.properties[(normals,v)].norm() - 1.).abs() < 1e-6);
+    /// }
+    /// ```
+    pub fn compute_vertex_normals(&mut self, weighting : NormalWeighting) -> Option<PropertyVertex<Vector3<f32>>> {
+        let mut sums = vec![[0.,0.,0.]; self.topology.vertices_size()];
+        match self.coords_reader() {
+            Some(position) => {
+                for f in self.topology.faces() {
+                    let n = self.area_face_normal(&*position,f);
+                    let unit = normalized(n);
+                    for h in self.topology.halfedges_around(f) {
+                        let v = self.topology.to_vertex(h);
+                        let weighted = match weighting {
+                            NormalWeighting::Uniform => unit,
+                            NormalWeighting::Area => scale(n,0.5),
+                            NormalWeighting::Angle => {
+                                let p = position(v);
+                                let p0 = position(self.topology.from_vertex(h));
+                                let p1 = position(self.topology.to_vertex(self.topology.next_halfedge(h)));
+                                scale(unit,corner_angle(sub(p0,p),sub(p1,p)))
+                            },
+                        };
+                        sums[v.idx()] = add(sums[v.idx()],weighted);
+                    }
+                }
+            },
+            None => return None,
+        }
+        let normals = match self.properties.get_vertex_property::<Vector3<f32>>("v:normal") {
+            Some(prop) => prop,
+            None => match self.properties.add_vertex_property::<Vector3<f32>>("v:normal",Vector3::new(0f32,0f32,0f32)) {
+                Some(prop) => prop,
+                None => return None,
+            },
+        };
+
+        let vertices : Vec<Vertex> = self.topology.vertices().collect();
+        for v in vertices {
+            self.properties[(normals,v)] = to_vector(normalized(sums[v.idx()]));
+        }
+        Some(normals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mesh::Mesh;
+    use handle::Vertex;
+    use nalgebra::Vector3;
+
+    fn close(a : Vector3<f32>, b : Vector3<f32>) -> bool {
+        (a-b).norm() < 1e-5
+    }
+
+    /// Builds a large triangle and a small triangle sharing `v0`, with different normals.
+    fn two_triangles() -> (Mesh, Vertex) {
+        let mut m = Mesh::new();
+        let pos = m.properties.add_vertex_property::<Vector3<f32>>("v:position",Vector3::new(0f32,0f32,0f32)).unwrap();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        let v3 = m.add_vertex();
+        m.properties[(pos,v0)] = Vector3::new(0.,0.,0.);
+        m.properties[(pos,v1)] = Vector3::new(4.,0.,0.);
+        m.properties[(pos,v2)] = Vector3::new(0.,4.,0.);
+        m.properties[(pos,v3)] = Vector3::new(0.,1.,-1.);
+        m.add_face(&vec![v0,v1,v2]).unwrap();
+        m.add_face(&vec![v0,v2,v3]).unwrap();
+        (m,v0)
+    }
+
+    #[test]
+    fn face_normals() {
+        let (mut m, _) = two_triangles();
+        let normals = m.compute_face_normals().unwrap();
+        assert!(close(m.properties[(normals,Face::new(0))],Vector3::new(0.,0.,1.)));
+        assert!(close(m.properties[(normals,Face::new(1))],Vector3::new(-1.,0.,0.)));
+    }
+
+    #[test]
+    fn vertex_normals() {
+        let (mut m, v0) = two_triangles();
+
+        let normals = m.compute_vertex_normals(NormalWeighting::Uniform).unwrap();
+        let s = 0.5f32.sqrt();
+        assert!(close(m.properties[(normals,v0)],Vector3::new(-s,0.,s)));
+
+        m.compute_vertex_normals(NormalWeighting::Area).unwrap();
+        assert!(close(m.properties[(normals,v0)],Vector3::new(-1.,0.,4.).normalize()));
+
+        m.compute_vertex_normals(NormalWeighting::Angle).unwrap();
+        assert!(close(m.properties[(normals,v0)],Vector3::new(-1.,0.,2.).normalize()));
+    }
+
+    #[test]
+    fn normals_far_from_origin() {
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f64;3]>().unwrap();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        m.properties[(pos,v0)] = [1e8,1e8,0.];
+        m.properties[(pos,v1)] = [1e8 + 1.,1e8,0.];
+        m.properties[(pos,v2)] = [1e8,1e8 + 1.,0.];
+        m.add_face(&vec![v0,v1,v2]).unwrap();
+        let normals = m.compute_face_normals().unwrap();
+        assert!(close(m.properties[(normals,Face::new(0))],Vector3::new(0.,0.,1.)));
+        let normals = m.compute_vertex_normals(NormalWeighting::Area).unwrap();
+        assert!(close(m.properties[(normals,v2)],Vector3::new(0.,0.,1.)));
+    }
+
+    #[test]
+    fn missing_position() {
+        let mut m = Mesh::new();
+        assert!(m.compute_face_normals().is_none());
+        assert!(m.compute_vertex_normals(NormalWeighting::Uniform).is_none());
+    }
+}
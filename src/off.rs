@@ -0,0 +1,370 @@
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write;
+use mesh::*;
+use handle::*;
+use mesh_io::*;
+use nalgebra::Vector3;
+use geometry::{Point, Scalar};
+use mesh_iterator::VerticesAround;
+
+/// Reads the non empty lines of an OFF file, without their comments.
+struct OffReader<R : io::Read> {
+    input_ : BufReader<R>,
+}
+
+impl<R : io::Read> OffReader<R> {
+    fn next_line(&mut self) -> Result<Vec<String>,MeshLoadingError> {
+        loop {
+            let mut line = String::new();
+            if try!(self.input_.read_line(&mut line)) == 0 {
+                return invalid(String::from("unexpected end of OFF file"));
+            }
+            let tokens : Vec<String> = line.split('#').next().unwrap().split_whitespace().map(String::from).collect();
+            if !tokens.is_empty() {
+                return Ok(tokens);
+            }
+        }
+    }
+}
+
+fn parse<T : ::std::str::FromStr>(token : &str) -> Result<T,MeshLoadingError> {
+    match token.parse::<T>() {
+        Ok(x) => Ok(x),
+        Err(_) => invalid(format!("invalid OFF value `{}`",token)),
+    }
+}
+
+fn parse_vector(tokens : &[String]) -> Result<Vector3<f32>,MeshLoadingError> {
+    Ok(Vector3::new(try!(parse::<f32>(&tokens[0])),try!(parse::<f32>(&tokens[1])),try!(parse::<f32>(&tokens[2]))))
+}
+
+fn parse_point<P : Point>(tokens : &[String]) -> Result<P,MeshLoadingError> {
+    let x = try!(parse::<f64>(&tokens[0]));
+    let y = try!(parse::<f64>(&tokens[1]));
+    let z = try!(parse::<f64>(&tokens[2]));
+    Ok(P::from_coords(Scalar::from_f64(x),Scalar::from_f64(y),Scalar::from_f64(z)))
+}
+
+/// Parses a RGB or RGBA color, integer components are in `[0,255]` and floating point ones in `[0,1]`.
+fn parse_color(tokens : &[String]) -> Result<Option<Vector3<f32>>,MeshLoadingError> {
+    if tokens.len() < 3 {
+        return Ok(None);
+    }
+    let c = try!(parse_vector(tokens));
+    if tokens.iter().all(|t| !t.contains('.')) {
+        Ok(Some(c / 255.))
+    } else {
+        Ok(Some(c))
+    }
+}
+
+impl Mesh {
+    /// Load Off `Mesh` from any `Read`.
+    ///
+    /// The `OFF`, `COFF`, `NOFF` and `CNOFF` variants are supported. The vertex normals and colors
+    /// are stored in `v:normal` and `v:color`, the face colors in `f:color`. Colors are stored as
+    /// floating point values in `[0,1]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let input = "OFF\n3 1 3\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n";
+    /// let mut m = Mesh::load_off(input.as_bytes()).ok().unwrap();
+    /// assert_eq!(m.topology.n_faces(),1);
+    /// ```
+    pub fn load_off<R : io::Read>(input : R) -> Result<Mesh,MeshLoadingError> {
+        Mesh::load_off_as::<Vector3<f32>,R>(input)
+    }
+
+    /// Load Off `Mesh` from any `Read`, storing the vertex positions as `P`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let input = "OFF\n3 1 3\n0 0 0\n1e-300 0 0\n0 1 0\n3 0 1 2\n";
+    /// let mut m = Mesh::load_off_as::<[f64;3],_>(input.as_bytes()).ok().unwrap();
+    /// let pos = m.positions::<[f64;3]>().unwrap();
+    /// assert_eq!(m.properties[(pos,Vertex::new(1))],[1e-300,0.,0.]);
+    /// ```
+    pub fn load_off_as<P : Point, R : io::Read>(input : R) -> Result<Mesh,MeshLoadingError> {
+        let mut reader = OffReader {
+            input_ : BufReader::new(input),
+        };
+
+        // header
+        let mut tokens = try!(reader.next_line());
+        let (has_color,has_normal) = match tokens[0].as_str() {
+            "OFF" => (false,false),
+            "COFF" => (true,false),
+            "NOFF" => (false,true),
+            "CNOFF" => (true,true),
+            _ => return invalid(format!("unsupported OFF header `{}`",tokens[0])),
+        };
+        tokens.remove(0);
+        if tokens.is_empty() {
+            tokens = try!(reader.next_line());
+        }
+        if tokens.len() < 2 {
+            return invalid(String::from("missing OFF element counts"));
+        }
+        let nv = try!(parse::<usize>(&tokens[0]));
+        let nf = try!(parse::<usize>(&tokens[1]));
+
+        let mut m = Mesh::new();
+        let zero = Vector3::new(0f32,0f32,0f32);
+        let pos = m.add_positions::<P>().unwrap();
+        let normal = if has_normal {m.properties.add_vertex_property::<Vector3<f32>>("v:normal",zero)} else {None};
+        let color = if has_color {m.properties.add_vertex_property::<Vector3<f32>>("v:color",zero)} else {None};
+        let mut fcolor = None;
+
+        // vertices
+        let nvalues = 3 + if has_normal {3} else {0} + if has_color {3} else {0};
+        m.vertex_reserve(nv.min(RESERVE_LIMIT));
+        for _ in 0..nv {
+            let tokens = try!(reader.next_line());
+            if tokens.len() < nvalues {
+                return invalid(String::from("missing OFF vertex values"));
+            }
+            let v = m.add_vertex();
+            m.properties[(pos,v)] = try!(parse_point::<P>(&tokens[0..3]));
+            let mut i = 3;
+            if let Some(p) = normal {
+                m.properties[(p,v)] = try!(parse_vector(&tokens[i..i+3]));
+                i += 3;
+            }
+            if let Some(p) = color {
+                m.properties[(p,v)] = try!(parse_color(&tokens[i..])).unwrap();
+            }
+        }
+
+        // faces
+        m.face_reserve(nf.min(RESERVE_LIMIT));
+        for _ in 0..nf {
+            let tokens = try!(reader.next_line());
+            let n = try!(parse::<usize>(&tokens[0]));
+            // the first token is the count, tokens is never empty
+            if tokens.len() - 1 < n {
+                return invalid(String::from("missing OFF face indices"));
+            }
+            let mut vvec : Vec<Vertex> = Vec::new();
+            for t in tokens[1..n+1].iter() {
+                vvec.push(Vertex::new(try!(parse::<usize>(t))));
+            }
+            let f = try!(add_loaded_face(&mut m,&mut vvec));
+            if let Some(c) = try!(parse_color(&tokens[n+1..])) {
+                if fcolor.is_none() {
+                    fcolor = m.properties.add_face_property::<Vector3<f32>>("f:color",zero);
+                }
+                m.properties[(fcolor.unwrap(),f)] = c;
+            }
+        }
+        return Ok(m);
+    }
+
+    /// Write Off `Mesh` into any `Write`.
+    ///
+    /// The properties `v:normal`, `v:color` and `f:color` are exported when they exist,
+    /// colors are written as RGBA integers in `[0,255]`. The positions are written with
+    /// their stored precision.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let mut m = Mesh::load("cube.obj").ok().unwrap();
+    /// let mut output = Vec::<u8>::new();
+    /// assert!(m.write_off(&mut output).is_ok());
+    /// ```
+    pub fn write_off<W : io::Write>(&self, output : W) -> Result<(),MeshLoadingError> {
+        let position = match self.coords_reader() {
+            Some(prop) => prop,
+            None => return Err(MeshLoadingError::MissingPosition),
+        };
+        let double = self.has_double_positions();
+        let normal = self.properties.get_vertex_property::<Vector3<f32>>("v:normal");
+        let color = self.properties.get_vertex_property::<Vector3<f32>>("v:color");
+        let fcolor = self.properties.get_face_property::<Vector3<f32>>("f:color");
+
+        let mut output = BufWriter::new(output);
+        let header = match (color.is_some(),normal.is_some()) {
+            (false,false) => "OFF",
+            (true,false) => "COFF",
+            (false,true) => "NOFF",
+            (true,true) => "CNOFF",
+        };
+        try!(write!(output,"{}\n",header));
+        try!(write!(output,"{} {} {}\n",self.topology.n_vertices(),self.topology.n_faces(),self.topology.n_edges()));
+
+        // vertices are renumbered to skip the deleted ones
+        let mut vindex : Vec<usize> = vec![0;self.topology.vertices_size()];
+        for (i,v) in self.topology.vertices().enumerate() {
+            vindex[v.idx()] = i;
+            let p = position(v);
+            if double {
+                try!(write!(output,"{} {} {}",p[0],p[1],p[2]));
+            } else {
+                try!(write!(output,"{} {} {}",p[0] as f32,p[1] as f32,p[2] as f32));
+            }
+            if let Some(prop) = normal {
+                let n = self.properties[(prop,v)];
+                try!(write!(output," {} {} {}",n.x,n.y,n.z));
+            }
+            if let Some(prop) = color {
+                let c = self.properties[(prop,v)] * 255.;
+                try!(write!(output," {} {} {} 255",c.x.round() as u8,c.y.round() as u8,c.z.round() as u8));
+            }
+            try!(write!(output,"\n"));
+        }
+
+        for f in self.topology.faces() {
+            try!(write!(output,"{}",self.topology.vertices_around(f).count()));
+            for v in self.topology.vertices_around(f) {
+                try!(write!(output," {}",vindex[v.idx()]));
+            }
+            if let Some(prop) = fcolor {
+                let c = self.properties[(prop,f)] * 255.;
+                try!(write!(output," {} {} {} 255",c.x.round() as u8,c.y.round() as u8,c.z.round() as u8));
+            }
+            try!(write!(output,"\n"));
+        }
+
+        try!(output.flush());
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mesh::Mesh;
+    use nalgebra::Vector3;
+    use std::str;
+
+    #[test]
+    fn load_off() {
+        let input =
+        r#"OFF
+# a comment
+4 2 0
+
+0 0 0
+1 0 0 # another comment
+0 1 0
+1 1 0
+3 0 1 2
+3 2 1 3 255 0 0
+"#;
+        let m = Mesh::load_off(input.as_bytes()).ok().unwrap();
+        assert_eq!(m.topology.n_vertices(),4);
+        assert_eq!(m.topology.n_faces(),2);
+        let pos = m.properties.get_vertex_property::<Vector3<f32>>("v:position").unwrap();
+        assert_eq!(m.properties[(pos,Vertex::new(3))],Vector3::new(1.,1.,0.));
+        let fcolor = m.properties.get_face_property::<Vector3<f32>>("f:color").unwrap();
+        assert_eq!(m.properties[(fcolor,Face::new(1))],Vector3::new(1.,0.,0.));
+        assert_eq!(m.properties[(fcolor,Face::new(0))],Vector3::new(0.,0.,0.));
+    }
+
+    #[test]
+    fn load_cnoff() {
+        let input =
+        r#"CNOFF 3 1 3
+0 0 0 0 0 1 1.0 0.5 0.0
+1 0 0 0 0 1 0 0 255 255
+0 1 0 0 0 1 0.0 1.0 0.0 1.0
+3 0 1 2
+"#;
+        let m = Mesh::load_off(input.as_bytes()).ok().unwrap();
+        assert_eq!(m.topology.n_faces(),1);
+        let normal = m.properties.get_vertex_property::<Vector3<f32>>("v:normal").unwrap();
+        assert_eq!(m.properties[(normal,Vertex::new(1))],Vector3::new(0.,0.,1.));
+        let color = m.properties.get_vertex_property::<Vector3<f32>>("v:color").unwrap();
+        assert_eq!(m.properties[(color,Vertex::new(0))],Vector3::new(1.,0.5,0.));
+        assert_eq!(m.properties[(color,Vertex::new(1))],Vector3::new(0.,0.,1.));
+        assert_eq!(m.properties[(color,Vertex::new(2))],Vector3::new(0.,1.,0.));
+    }
+
+    #[test]
+    fn write_coff() {
+        let mut m = Mesh::new();
+        let pos = m.properties.add_vertex_property::<Vector3<f32>>("v:position",Vector3::new(0f32,0f32,0f32)).unwrap();
+        let color = m.properties.add_vertex_property::<Vector3<f32>>("v:color",Vector3::new(1f32,0f32,0f32)).unwrap();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        m.properties[(pos,v1)] = Vector3::new(1.,0.,0.);
+        let v2 = m.add_vertex();
+        m.properties[(pos,v2)] = Vector3::new(0.,1.,0.);
+        m.properties[(color,v2)] = Vector3::new(0.,0.,1.);
+        m.add_face(&vec![v0,v1,v2]);
+        let expected =
+        r#"COFF
+3 1 3
+0 0 0 255 0 0 255
+1 0 0 255 0 0 255
+0 1 0 0 0 255 255
+3 0 1 2
+"#;
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_off(&mut output).is_ok());
+        assert_eq!(expected,str::from_utf8(&output).unwrap());
+
+        let m2 = Mesh::load_off(&output[..]).ok().unwrap();
+        let color = m2.properties.get_vertex_property::<Vector3<f32>>("v:color").unwrap();
+        assert_eq!(m2.properties[(color,Vertex::new(2))],Vector3::new(0.,0.,1.));
+    }
+
+    #[test]
+    fn write_off_f64() {
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f64;3]>().unwrap();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        m.properties[(pos,v0)] = [0.1,0.,1e9 + 0.5];
+        m.properties[(pos,v2)] = [0.,1.,0.];
+        m.add_face(&vec![v0,v1,v2]);
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_off(&mut output).is_ok());
+        assert_eq!("OFF\n3 1 3\n0.1 0 1000000000.5\n0 0 0\n0 1 0\n3 0 1 2\n",str::from_utf8(&output).unwrap());
+
+        // the f64 coordinates are read back without loss
+        let m2 = Mesh::load_off_as::<[f64;3],_>(&output[..]).ok().unwrap();
+        let pos2 = m2.positions::<[f64;3]>().unwrap();
+        assert_eq!(m2.properties[(pos2,v0)],[0.1,0.,1e9 + 0.5]);
+    }
+
+    #[test]
+    fn load_off_invalid() {
+        match Mesh::load_off("4OFF\n".as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        match Mesh::load_off("OFF\n3 1 3\n0 0 0\n1 0 0\n".as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        match Mesh::load_off("OFF\n3 1 3\n0 0 0\n1 0 0\n0 1 0\n2 0 1\n".as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        match Mesh::load_off("NOFF\n1 0 0\n0 0 0\n".as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        match Mesh::load_off("OFF\n18446744073709551615 18446744073709551615 0\n0 0 0\n".as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        match Mesh::load_off("OFF\n3 1 3\n0 0 0\n1 0 0\n0 1 0\n18446744073709551615 0 1 2\n".as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+    }
+}
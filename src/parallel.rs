@@ -0,0 +1,182 @@
+use rayon::prelude::*;
+use mesh::*;
+use handle::*;
+
+impl Topology {
+    /// Parallel iterator over the vertices in the `Mesh`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// extern crate lwmesh;
+    /// use rayon::prelude::*;
+    /// use lwmesh::*;
+    ///
+    /// # fn main() {
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// for &(a,b,c) in &[(0,2,1),(0,1,3),(1,2,3),(0,3,2)] {
+    ///     m.add_face(&vec![vvec[a],vvec[b],vvec[c]]).unwrap();
+    /// }
+    /// let topology = &m.topology;
+    /// let valences : usize = topology.par_vertices().map(|v| topology.halfedges_around(v).count()).sum();
+    /// assert_eq!(valences,topology.n_halfedges());
+    /// # }
+    /// ```
+    pub fn par_vertices<'a>(&'a self) -> impl ParallelIterator<Item = Vertex> + 'a {
+        (0..self.vertices_size()).into_par_iter()
+            .map(Vertex::new)
+            .filter(move |&v| !self.is_deleted_vertex(v))
+    }
+
+    /// Parallel iterator over the faces in the `Mesh`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// extern crate lwmesh;
+    /// use rayon::prelude::*;
+    /// use lwmesh::*;
+    ///
+    /// # fn main() {
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// for &(a,b,c) in &[(0,2,1),(0,1,3),(1,2,3),(0,3,2)] {
+    ///     m.add_face(&vec![vvec[a],vvec[b],vvec[c]]).unwrap();
+    /// }
+    /// assert_eq!(m.topology.par_faces().count(),m.topology.n_faces());
+    /// # }
+    /// ```
+    pub fn par_faces<'a>(&'a self) -> impl ParallelIterator<Item = Face> + 'a {
+        (0..self.faces_size()).into_par_iter()
+            .map(Face::new)
+            .filter(move |&f| !self.is_deleted_face(f))
+    }
+
+    /// Parallel iterator over the edges in the `Mesh`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// extern crate lwmesh;
+    /// use rayon::prelude::*;
+    /// use lwmesh::*;
+    ///
+    /// # fn main() {
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// for &(a,b,c) in &[(0,2,1),(0,1,3),(1,2,3),(0,3,2)] {
+    ///     m.add_face(&vec![vvec[a],vvec[b],vvec[c]]).unwrap();
+    /// }
+    /// let topology = &m.topology;
+    /// assert_eq!(topology.par_edges().filter(|&e| topology.is_boundary_edge(e)).count(),0);
+    /// # }
+    /// ```
+    pub fn par_edges<'a>(&'a self) -> impl ParallelIterator<Item = Edge> + 'a {
+        (0..self.edges_size()).into_par_iter()
+            .map(Edge::new)
+            .filter(move |&e| !self.is_deleted_edge(e))
+    }
+}
+
+impl Properties {
+    /// Parallel iterator over the elements of the property `p`, each paired with its handle.
+    ///
+    /// Every element can only be written through its own slot, so the other elements of the
+    /// `Mesh` are read from the `Topology` or from other `Mesh`es. The elements of the deleted
+    /// handles are included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// extern crate lwmesh;
+    /// use rayon::prelude::*;
+    /// use lwmesh::*;
+    ///
+    /// # fn main() {
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// for &(a,b,c) in &[(0,2,1),(0,1,3),(1,2,3),(0,3,2)] {
+    ///     m.add_face(&vec![vvec[a],vvec[b],vvec[c]]).unwrap();
+    /// }
+    /// let valence = m.properties.add_vertex_property::<usize>("v:valence",0).unwrap();
+    /// let topology = &m.topology;
+    /// m.properties.par_property_mut(valence).for_each(|(v,x)| *x = topology.halfedges_around(v).count());
+    /// for v in m.topology.vertices() {
+    ///     assert_eq!(m.properties[(valence,v)],m.topology.halfedges_around(v).count());
+    /// }
+    /// # }
+    /// ```
+    pub fn par_property_mut<'a, T : PropertyElement, D : 'static + Send>(&'a mut self, p : PropertyHandle<(T,D)>)
+        -> impl IndexedParallelIterator<Item = (Handle<T>, &'a mut D)> + 'a
+        where Handle<T> : Send {
+        let data = match T::container_mut(self).slice_mut(p) {
+            Ok(x) => x,
+            Err(e) => panic!("invalid property access: {:?}",e),
+        };
+        data.par_iter_mut()
+            .enumerate()
+            .map(|(i,x)| (Handle::new(i),x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::prelude::*;
+    use mesh::*;
+    use handle::*;
+
+    /// Builds a strip of `n` quads with positions stored as `[f32;3]`.
+    fn strip(n : usize) -> Mesh {
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f32;3]>().unwrap();
+        for i in 0..n+1 {
+            let v0 = m.add_vertex();
+            let v1 = m.add_vertex();
+            m.properties[(pos,v0)] = [i as f32,0.,0.];
+            m.properties[(pos,v1)] = [i as f32,1.,0.];
+        }
+        for i in 0..n {
+            let vs = vec![Vertex::new(2*i),Vertex::new(2*i+2),Vertex::new(2*i+3),Vertex::new(2*i+1)];
+            m.add_face(&vs).unwrap();
+        }
+        m
+    }
+
+    #[test]
+    fn par_elements() {
+        let mut m = strip(10);
+        m.delete_face(Face::new(3));
+        m.delete_vertex(Vertex::new(0));
+
+        let mut faces : Vec<Face> = m.topology.par_faces().collect();
+        faces.sort();
+        assert_eq!(faces,m.topology.faces().collect::<Vec<Face>>());
+        let mut edges : Vec<Edge> = m.topology.par_edges().collect();
+        edges.sort();
+        assert_eq!(edges,m.topology.edges().collect::<Vec<Edge>>());
+        let mut vertices : Vec<Vertex> = m.topology.par_vertices().collect();
+        vertices.sort();
+        assert_eq!(vertices,m.topology.vertices().collect::<Vec<Vertex>>());
+    }
+
+    #[test]
+    fn par_property_mut() {
+        let m = strip(10);
+        let pos = m.positions::<[f32;3]>().unwrap();
+        let mut scaled = m.clone();
+        let old = m.properties.property_slice(pos);
+        scaled.properties.par_property_mut(pos).for_each(|(v,p)| {
+            let q = old[v.idx()];
+            *p = [2. * q[0],2. * q[1],2. * q[2]];
+        });
+        for v in m.topology.vertices() {
+            let q = m.properties[(pos,v)];
+            assert_eq!(scaled.properties[(pos,v)],[2. * q[0],2. * q[1],2. * q[2]]);
+        }
+    }
+}
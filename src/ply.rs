@@ -0,0 +1,1016 @@
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use mesh::*;
+use handle::*;
+use mesh_io::*;
+use nalgebra::Vector3;
+use geometry::{Point, Scalar};
+use mesh_iterator::VerticesAround;
+
+/// The encoding of the data stored in a PLY file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+/// The scalar types of the PLY properties.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum PlyType {
+    Char,
+    UChar,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Float,
+    Double,
+}
+
+impl PlyType {
+    fn parse(name : &str) -> Option<PlyType> {
+        match name {
+            "char" | "int8" => Some(PlyType::Char),
+            "uchar" | "uint8" => Some(PlyType::UChar),
+            "short" | "int16" => Some(PlyType::Short),
+            "ushort" | "uint16" => Some(PlyType::UShort),
+            "int" | "int32" => Some(PlyType::Int),
+            "uint" | "uint32" => Some(PlyType::UInt),
+            "float" | "float32" => Some(PlyType::Float),
+            "double" | "float64" => Some(PlyType::Double),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            PlyType::Char => "char",
+            PlyType::UChar => "uchar",
+            PlyType::Short => "short",
+            PlyType::UShort => "ushort",
+            PlyType::Int => "int",
+            PlyType::UInt => "uint",
+            PlyType::Float => "float",
+            PlyType::Double => "double",
+        }
+    }
+
+    fn size(&self) -> usize {
+        match *self {
+            PlyType::Char | PlyType::UChar => 1,
+            PlyType::Short | PlyType::UShort => 2,
+            PlyType::Int | PlyType::UInt | PlyType::Float => 4,
+            PlyType::Double => 8,
+        }
+    }
+}
+
+/// A property of a PLY element. `list_` is the type of the item count for list properties.
+struct PlyProperty {
+    name_ : String,
+    type_ : PlyType,
+    list_ : Option<PlyType>,
+}
+
+/// An element of a PLY file, as declared in the header.
+struct PlyElement {
+    name_ : String,
+    count_ : usize,
+    properties_ : Vec<PlyProperty>,
+}
+
+impl PlyElement {
+    fn find(&self, name : &str) -> Option<usize> {
+        self.properties_.iter().position(|p| p.name_ == name && p.list_.is_none())
+    }
+
+    fn find3(&self, x : &str, y : &str, z : &str) -> Option<[usize;3]> {
+        match (self.find(x), self.find(y), self.find(z)) {
+            (Some(i), Some(j), Some(k)) => Some([i,j,k]),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the list property storing the vertex indices of a face.
+    fn find_indices(&self) -> Option<usize> {
+        self.properties_.iter().position(|p| (p.name_ == "vertex_indices" || p.name_ == "vertex_index") && p.list_.is_some())
+    }
+}
+
+/// The names of the PLY properties with a dedicated `Mesh` property.
+const KNOWN_PROPERTIES : [&str;10] = ["x","y","z","nx","ny","nz","red","green","blue","quality"];
+
+/// The PLY properties of an element which are stored as `Properties`.
+/// `extra_` lists the other scalar properties, stored under their own name.
+struct PlyAttributes {
+    normal_ : Option<[usize;3]>,
+    color_ : Option<[usize;3]>,
+    color_scale_ : f32,
+    quality_ : Option<usize>,
+    extra_ : Vec<usize>,
+}
+
+/// A `Mesh` property storing an extra scalar PLY property, typed after the PLY type.
+#[derive(Copy, Clone)]
+enum PlyExtra<T> {
+    Char(PropertyHandle<(T,i8)>),
+    UChar(PropertyHandle<(T,u8)>),
+    Short(PropertyHandle<(T,i16)>),
+    UShort(PropertyHandle<(T,u16)>),
+    Int(PropertyHandle<(T,i32)>),
+    UInt(PropertyHandle<(T,u32)>),
+    Float(PropertyHandle<(T,f32)>),
+    Double(PropertyHandle<(T,f64)>),
+}
+
+impl<T : PropertyElement + Copy> PlyExtra<T> {
+    /// Adds the property `name` with the Rust type of the PLY type `t`.
+    /// Returns `None` if the name is already used.
+    fn add(properties : &mut Properties, name : &str, t : PlyType) -> Option<PlyExtra<T>> {
+        let container = T::container_mut(properties);
+        match t {
+            PlyType::Char => container.add::<i8>(name,0).map(PlyExtra::Char),
+            PlyType::UChar => container.add::<u8>(name,0).map(PlyExtra::UChar),
+            PlyType::Short => container.add::<i16>(name,0).map(PlyExtra::Short),
+            PlyType::UShort => container.add::<u16>(name,0).map(PlyExtra::UShort),
+            PlyType::Int => container.add::<i32>(name,0).map(PlyExtra::Int),
+            PlyType::UInt => container.add::<u32>(name,0).map(PlyExtra::UInt),
+            PlyType::Float => container.add::<f32>(name,0.).map(PlyExtra::Float),
+            PlyType::Double => container.add::<f64>(name,0.).map(PlyExtra::Double),
+        }
+    }
+
+    /// Returns the property `name` if it is stored with the Rust type of a PLY type.
+    fn get(properties : &Properties, name : &str) -> Option<PlyExtra<T>> {
+        let container = T::container(properties);
+        container.get::<i8>(name).map(PlyExtra::Char)
+            .or_else(|| container.get::<u8>(name).map(PlyExtra::UChar))
+            .or_else(|| container.get::<i16>(name).map(PlyExtra::Short))
+            .or_else(|| container.get::<u16>(name).map(PlyExtra::UShort))
+            .or_else(|| container.get::<i32>(name).map(PlyExtra::Int))
+            .or_else(|| container.get::<u32>(name).map(PlyExtra::UInt))
+            .or_else(|| container.get::<f32>(name).map(PlyExtra::Float))
+            .or_else(|| container.get::<f64>(name).map(PlyExtra::Double))
+    }
+
+    fn ply_type(&self) -> PlyType {
+        match *self {
+            PlyExtra::Char(_) => PlyType::Char,
+            PlyExtra::UChar(_) => PlyType::UChar,
+            PlyExtra::Short(_) => PlyType::Short,
+            PlyExtra::UShort(_) => PlyType::UShort,
+            PlyExtra::Int(_) => PlyType::Int,
+            PlyExtra::UInt(_) => PlyType::UInt,
+            PlyExtra::Float(_) => PlyType::Float,
+            PlyExtra::Double(_) => PlyType::Double,
+        }
+    }
+
+    /// Stores the value `x` read from the file, which has the PLY type of the property.
+    fn set(&self, properties : &mut Properties, h : Handle<T>, x : f64) {
+        match *self {
+            PlyExtra::Char(p) => *properties.try_get_mut(p,h).unwrap() = x as i8,
+            PlyExtra::UChar(p) => *properties.try_get_mut(p,h).unwrap() = x as u8,
+            PlyExtra::Short(p) => *properties.try_get_mut(p,h).unwrap() = x as i16,
+            PlyExtra::UShort(p) => *properties.try_get_mut(p,h).unwrap() = x as u16,
+            PlyExtra::Int(p) => *properties.try_get_mut(p,h).unwrap() = x as i32,
+            PlyExtra::UInt(p) => *properties.try_get_mut(p,h).unwrap() = x as u32,
+            PlyExtra::Float(p) => *properties.try_get_mut(p,h).unwrap() = x as f32,
+            PlyExtra::Double(p) => *properties.try_get_mut(p,h).unwrap() = x,
+        }
+    }
+
+    /// Returns the value to write, which every PLY type represents exactly as a `f64`.
+    fn value(&self, properties : &Properties, h : Handle<T>) -> f64 {
+        match *self {
+            PlyExtra::Char(p) => *properties.try_get(p,h).unwrap() as f64,
+            PlyExtra::UChar(p) => *properties.try_get(p,h).unwrap() as f64,
+            PlyExtra::Short(p) => *properties.try_get(p,h).unwrap() as f64,
+            PlyExtra::UShort(p) => *properties.try_get(p,h).unwrap() as f64,
+            PlyExtra::Int(p) => *properties.try_get(p,h).unwrap() as f64,
+            PlyExtra::UInt(p) => *properties.try_get(p,h).unwrap() as f64,
+            PlyExtra::Float(p) => *properties.try_get(p,h).unwrap() as f64,
+            PlyExtra::Double(p) => *properties.try_get(p,h).unwrap(),
+        }
+    }
+}
+
+/// Returns the extra properties to write for the element `prefix`, as PLY names and `Mesh` properties.
+fn extra_properties<T : PropertyElement + Copy>(properties : &Properties, prefix : &str) -> Vec<(String,PlyExtra<T>)> {
+    let mut extra = Vec::new();
+    for name in T::container(properties).names() {
+        if !name.starts_with(prefix) {
+            continue;
+        }
+        let ply_name = &name[prefix.len()..];
+        if ply_name.is_empty() || KNOWN_PROPERTIES.contains(&ply_name) || ply_name.contains(char::is_whitespace) {
+            continue;
+        }
+        if let Some(p) = PlyExtra::get(properties,name) {
+            extra.push((String::from(ply_name),p));
+        }
+    }
+    extra
+}
+
+impl PlyAttributes {
+    fn new(element : &PlyElement) -> PlyAttributes {
+        let color = element.find3("red","green","blue");
+        let color_scale = match color {
+            Some(c) if element.properties_[c[0]].type_ == PlyType::UChar => 1./255.,
+            _ => 1.,
+        };
+        PlyAttributes {
+            normal_ : element.find3("nx","ny","nz"),
+            color_ : color,
+            color_scale_ : color_scale,
+            quality_ : element.find("quality"),
+            extra_ : element.properties_.iter().enumerate()
+                .filter(|&(_,p)| p.list_.is_none() && !KNOWN_PROPERTIES.contains(&p.name_.as_str()))
+                .map(|(i,_)| i).collect(),
+        }
+    }
+
+    fn normal(&self, values : &Vec<f64>) -> Option<Vector3<f32>> {
+        self.normal_.map(|i| Vector3::new(values[i[0]] as f32, values[i[1]] as f32, values[i[2]] as f32))
+    }
+
+    fn color(&self, values : &Vec<f64>) -> Option<Vector3<f32>> {
+        let s = self.color_scale_;
+        self.color_.map(|i| Vector3::new(values[i[0]] as f32 * s, values[i[1]] as f32 * s, values[i[2]] as f32 * s))
+    }
+
+    fn quality(&self, values : &Vec<f64>) -> Option<f32> {
+        self.quality_.map(|i| values[i] as f32)
+    }
+
+    /// Adds a `Mesh` property for each extra PLY property, named `prefix` followed by the PLY name.
+    /// The properties whose name is already used are skipped.
+    fn add_extra<T : PropertyElement + Copy>(&self, properties : &mut Properties, element : &PlyElement, prefix : &str) -> Vec<(usize,PlyExtra<T>)> {
+        let mut extra = Vec::new();
+        for &i in self.extra_.iter() {
+            let p = &element.properties_[i];
+            if let Some(e) = PlyExtra::add(properties,&format!("{}{}",prefix,p.name_),p.type_) {
+                extra.push((i,e));
+            }
+        }
+        extra
+    }
+}
+
+/// Reads the header of a PLY file, up to the `end_header` line.
+fn read_header<R : Read>(input : &mut BufReader<R>) -> Result<(PlyFormat,Vec<PlyElement>),MeshLoadingError> {
+    let mut line = String::new();
+    try!(input.read_line(&mut line));
+    if line.trim() != "ply" {
+        return invalid(String::from("missing PLY magic number"));
+    }
+
+    let mut format = None;
+    let mut elements : Vec<PlyElement> = Vec::new();
+    loop {
+        line.clear();
+        if try!(input.read_line(&mut line)) == 0 {
+            return invalid(String::from("unexpected end of PLY header"));
+        }
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+        match tokens[0] {
+            "format" => {
+                if tokens.len() != 3 {
+                    return invalid(format!("invalid PLY format line `{}`",line.trim()));
+                }
+                format = match tokens[1] {
+                    "ascii" => Some(PlyFormat::Ascii),
+                    "binary_little_endian" => Some(PlyFormat::BinaryLittleEndian),
+                    "binary_big_endian" => Some(PlyFormat::BinaryBigEndian),
+                    _ => return invalid(format!("unknown PLY format `{}`",tokens[1])),
+                };
+            },
+            "element" => {
+                if tokens.len() != 3 {
+                    return invalid(format!("invalid PLY element line `{}`",line.trim()));
+                }
+                let count = match tokens[2].parse::<usize>() {
+                    Ok(c) => c,
+                    Err(_) => return invalid(format!("invalid PLY element count `{}`",tokens[2])),
+                };
+                elements.push(PlyElement {
+                    name_ : String::from(tokens[1]),
+                    count_ : count,
+                    properties_ : Vec::new(),
+                });
+            },
+            "property" => {
+                let property = if tokens.len() == 3 {
+                    PlyType::parse(tokens[1]).map(|t| PlyProperty {
+                        name_ : String::from(tokens[2]),
+                        type_ : t,
+                        list_ : None,
+                    })
+                } else if tokens.len() == 5 && tokens[1] == "list" {
+                    match (PlyType::parse(tokens[2]), PlyType::parse(tokens[3])) {
+                        (Some(c), Some(t)) => Some(PlyProperty {
+                            name_ : String::from(tokens[4]),
+                            type_ : t,
+                            list_ : Some(c),
+                        }),
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+                match (property, elements.last_mut()) {
+                    (Some(p), Some(e)) => e.properties_.push(p),
+                    _ => return invalid(format!("invalid PLY property line `{}`",line.trim())),
+                }
+            },
+            "comment" | "obj_info" => {},
+            "end_header" => break,
+            _ => return invalid(format!("invalid PLY header line `{}`",line.trim())),
+        }
+    }
+
+    match format {
+        Some(f) => Ok((f,elements)),
+        None => invalid(String::from("missing PLY format")),
+    }
+}
+
+/// Reads the values of the PLY elements, either as text or as binary.
+struct PlyReader<R : Read> {
+    input_ : BufReader<R>,
+    format_ : PlyFormat,
+    tokens_ : Vec<String>,
+}
+
+impl<R : Read> PlyReader<R> {
+    fn next_token(&mut self) -> Result<String,MeshLoadingError> {
+        while self.tokens_.is_empty() {
+            let mut line = String::new();
+            if try!(self.input_.read_line(&mut line)) == 0 {
+                return invalid(String::from("unexpected end of PLY data"));
+            }
+            self.tokens_ = line.split_whitespace().rev().map(String::from).collect();
+        }
+        Ok(self.tokens_.pop().unwrap())
+    }
+
+    fn read_value(&mut self, t : PlyType) -> Result<f64,MeshLoadingError> {
+        if self.format_ == PlyFormat::Ascii {
+            let token = try!(self.next_token());
+            return match token.parse::<f64>() {
+                Ok(x) => Ok(x),
+                Err(_) => invalid(format!("invalid PLY value `{}`",token)),
+            };
+        }
+
+        let n = t.size();
+        let mut b = [0u8;8];
+        try!(self.input_.read_exact(&mut b[..n]));
+        if self.format_ == PlyFormat::BinaryBigEndian {
+            b[..n].reverse();
+        }
+        let x = match t {
+            PlyType::Char => b[0] as i8 as f64,
+            PlyType::UChar => b[0] as f64,
+            PlyType::Short => i16::from_le_bytes([b[0],b[1]]) as f64,
+            PlyType::UShort => u16::from_le_bytes([b[0],b[1]]) as f64,
+            PlyType::Int => i32::from_le_bytes([b[0],b[1],b[2],b[3]]) as f64,
+            PlyType::UInt => u32::from_le_bytes([b[0],b[1],b[2],b[3]]) as f64,
+            PlyType::Float => f32::from_bits(u32::from_le_bytes([b[0],b[1],b[2],b[3]])) as f64,
+            PlyType::Double => f64::from_bits(u64::from_le_bytes(b)),
+        };
+        Ok(x)
+    }
+
+    /// Reads one element. The scalar properties are stored in `values`, list properties
+    /// are skipped except the one at index `list`, which is stored in `items`.
+    fn read_element(&mut self, element : &PlyElement, list : Option<usize>, values : &mut Vec<f64>, items : &mut Vec<usize>) -> Result<(),MeshLoadingError> {
+        values.clear();
+        items.clear();
+        for (i,p) in element.properties_.iter().enumerate() {
+            match p.list_ {
+                None => {
+                    let x = try!(self.read_value(p.type_));
+                    values.push(x);
+                },
+                Some(c) => {
+                    values.push(0.);
+                    let n = try!(self.read_value(c)) as usize;
+                    for _ in 0..n {
+                        let x = try!(self.read_value(p.type_));
+                        if Some(i) == list {
+                            if x < 0. || x.fract() != 0. {
+                                return invalid(format!("invalid vertex index {}",x));
+                            }
+                            items.push(x as usize);
+                        }
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the values of the PLY elements, either as text or as binary.
+struct PlyWriter<W : Write> {
+    output_ : BufWriter<W>,
+    format_ : PlyFormat,
+    first_ : bool,
+}
+
+impl<W : Write> PlyWriter<W> {
+    fn write_value(&mut self, t : PlyType, x : f64) -> Result<(),MeshLoadingError> {
+        if self.format_ == PlyFormat::Ascii {
+            if !self.first_ {
+                try!(write!(self.output_," "));
+            }
+            self.first_ = false;
+            match t {
+                PlyType::Float => try!(write!(self.output_,"{}",x as f32)),
+                PlyType::Double => try!(write!(self.output_,"{}",x)),
+                _ => try!(write!(self.output_,"{}",x as i64)),
+            }
+            return Ok(());
+        }
+
+        let mut b = [0u8;8];
+        let n = t.size();
+        match t {
+            PlyType::Char => b[0] = x as i8 as u8,
+            PlyType::UChar => b[0] = x as u8,
+            PlyType::Short => b[..2].copy_from_slice(&(x as i16).to_le_bytes()),
+            PlyType::UShort => b[..2].copy_from_slice(&(x as u16).to_le_bytes()),
+            PlyType::Int => b[..4].copy_from_slice(&(x as i32).to_le_bytes()),
+            PlyType::UInt => b[..4].copy_from_slice(&(x as u32).to_le_bytes()),
+            PlyType::Float => b[..4].copy_from_slice(&(x as f32).to_bits().to_le_bytes()),
+            PlyType::Double => b.copy_from_slice(&x.to_bits().to_le_bytes()),
+        }
+        if self.format_ == PlyFormat::BinaryBigEndian {
+            b[..n].reverse();
+        }
+        try!(self.output_.write_all(&b[..n]));
+        Ok(())
+    }
+
+    /// Writes the components of `x` multiplied by `scale`, rounded and clamped to `[0,255]` for `UChar`.
+    fn write_vector(&mut self, t : PlyType, x : Vector3<f32>, scale : f32) -> Result<(),MeshLoadingError> {
+        for &c in &[x.x,x.y,x.z] {
+            let c = (c*scale) as f64;
+            let c = if t == PlyType::UChar {c.round().max(0.).min(255.)} else {c};
+            try!(self.write_value(t,c));
+        }
+        Ok(())
+    }
+
+    fn end_element(&mut self) -> Result<(),MeshLoadingError> {
+        if self.format_ == PlyFormat::Ascii {
+            try!(write!(self.output_,"\n"));
+        }
+        self.first_ = true;
+        Ok(())
+    }
+}
+
+impl Mesh {
+    /// Load Ply `Mesh` from any `Read`.
+    ///
+    /// The vertex coordinates are stored in `v:position`. The normals, colors and quality of
+    /// the vertices and faces are stored in `v:normal`, `v:color`, `v:quality`, `f:normal`,
+    /// `f:color` and `f:quality`. Colors are stored as floating point values in `[0,1]`.
+    /// The other scalar properties are stored under their PLY name prefixed by `v:` or `f:`,
+    /// with the Rust type of their PLY type: `i8`, `u8`, `i16`, `u16`, `i32`, `u32`, `f32` or `f64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let input = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+    ///              element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+    ///              0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n";
+    /// let mut m = Mesh::load_ply(input.as_bytes()).ok().unwrap();
+    /// assert_eq!(m.topology.n_faces(),1);
+    /// ```
+    pub fn load_ply<R : io::Read>(input : R) -> Result<Mesh,MeshLoadingError> {
+        Mesh::load_ply_as::<Vector3<f32>,R>(input)
+    }
+
+    /// Load Ply `Mesh` from any `Read`, storing the vertex positions as `P`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let input = "ply\nformat ascii 1.0\nelement vertex 3\nproperty double x\nproperty double y\nproperty double z\n\
+    ///              element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+    ///              0 0 0\n1e-300 0 0\n0 1 0\n3 0 1 2\n";
+    /// let mut m = Mesh::load_ply_as::<[f64;3],_>(input.as_bytes()).ok().unwrap();
+    /// let pos = m.positions::<[f64;3]>().unwrap();
+    /// assert_eq!(m.properties[(pos,Vertex::new(1))],[1e-300,0.,0.]);
+    /// ```
+    pub fn load_ply_as<P : Point, R : io::Read>(input : R) -> Result<Mesh,MeshLoadingError> {
+        let mut input = BufReader::new(input);
+        let (format,elements) = try!(read_header(&mut input));
+        let mut reader = PlyReader {
+            input_ : input,
+            format_ : format,
+            tokens_ : Vec::new(),
+        };
+
+        let mut m = Mesh::new();
+        let zero = Vector3::new(0f32,0f32,0f32);
+        let pos = m.add_positions::<P>().unwrap();
+        let mut values : Vec<f64> = Vec::new();
+        let mut items : Vec<usize> = Vec::new();
+
+        for element in elements.iter() {
+            if element.name_ == "vertex" {
+                let xyz = match element.find3("x","y","z") {
+                    Some(xyz) => xyz,
+                    None => return Err(MeshLoadingError::MissingPosition),
+                };
+                let attributes = PlyAttributes::new(element);
+                let normal = attributes.normal_.map(|_| m.properties.add_vertex_property::<Vector3<f32>>("v:normal",zero).unwrap());
+                let color = attributes.color_.map(|_| m.properties.add_vertex_property::<Vector3<f32>>("v:color",zero).unwrap());
+                let quality = attributes.quality_.map(|_| m.properties.add_vertex_property::<f32>("v:quality",0.).unwrap());
+                let extra = attributes.add_extra(&mut m.properties,element,"v:");
+
+                m.vertex_reserve(element.count_.min(RESERVE_LIMIT));
+                for _ in 0..element.count_ {
+                    try!(reader.read_element(element,None,&mut values,&mut items));
+                    let v = m.add_vertex();
+                    m.properties[(pos,v)] = P::from_coords(Scalar::from_f64(values[xyz[0]]),Scalar::from_f64(values[xyz[1]]),Scalar::from_f64(values[xyz[2]]));
+                    if let Some(p) = normal {
+                        m.properties[(p,v)] = attributes.normal(&values).unwrap();
+                    }
+                    if let Some(p) = color {
+                        m.properties[(p,v)] = attributes.color(&values).unwrap();
+                    }
+                    if let Some(p) = quality {
+                        m.properties[(p,v)] = attributes.quality(&values).unwrap();
+                    }
+                    for &(i,e) in extra.iter() {
+                        e.set(&mut m.properties,v,values[i]);
+                    }
+                }
+            } else if element.name_ == "face" {
+                let indices = match element.find_indices() {
+                    Some(i) => i,
+                    None => return invalid(String::from("missing PLY vertex indices")),
+                };
+                let attributes = PlyAttributes::new(element);
+                let normal = attributes.normal_.map(|_| m.properties.add_face_property::<Vector3<f32>>("f:normal",zero).unwrap());
+                let color = attributes.color_.map(|_| m.properties.add_face_property::<Vector3<f32>>("f:color",zero).unwrap());
+                let quality = attributes.quality_.map(|_| m.properties.add_face_property::<f32>("f:quality",0.).unwrap());
+                let extra = attributes.add_extra(&mut m.properties,element,"f:");
+
+                m.face_reserve(element.count_.min(RESERVE_LIMIT));
+                for _ in 0..element.count_ {
+                    try!(reader.read_element(element,Some(indices),&mut values,&mut items));
+                    let mut vvec : Vec<Vertex> = items.iter().map(|&i| Vertex::new(i)).collect();
+                    let f = try!(add_loaded_face(&mut m,&mut vvec));
+                    if let Some(p) = normal {
+                        m.properties[(p,f)] = attributes.normal(&values).unwrap();
+                    }
+                    if let Some(p) = color {
+                        m.properties[(p,f)] = attributes.color(&values).unwrap();
+                    }
+                    if let Some(p) = quality {
+                        m.properties[(p,f)] = attributes.quality(&values).unwrap();
+                    }
+                    for &(i,e) in extra.iter() {
+                        e.set(&mut m.properties,f,values[i]);
+                    }
+                }
+            } else {
+                for _ in 0..element.count_ {
+                    try!(reader.read_element(element,None,&mut values,&mut items));
+                }
+            }
+        }
+        return Ok(m);
+    }
+
+    /// Write Ply `Mesh` into any `Write`.
+    ///
+    /// The properties `v:normal`, `v:color`, `v:quality`, `f:normal`, `f:color` and `f:quality`
+    /// are exported when they exist, as well as the other vertex and face properties stored with
+    /// one of the Rust types of the PLY scalar types, named after their property name without the
+    /// `v:` or `f:` prefix.
+    /// The positions are written as doubles if they are stored as `f64`, as floats otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::load("cube.obj").ok().unwrap();
+    /// let mut output = Vec::<u8>::new();
+    /// assert!(m.write_ply(&mut output,PlyFormat::Ascii).is_ok());
+    /// ```
+    pub fn write_ply<W : io::Write>(&self, output : W, format : PlyFormat) -> Result<(),MeshLoadingError> {
+        let position = match self.coords_reader() {
+            Some(prop) => prop,
+            None => return Err(MeshLoadingError::MissingPosition),
+        };
+        let ptype = if self.has_double_positions() {PlyType::Double} else {PlyType::Float};
+        // the face sizes are written as uchar unless a face is too large for it
+        let large = self.topology.faces().any(|f| self.topology.vertices_around(f).count() > 255);
+        let ctype = if large {PlyType::Int} else {PlyType::UChar};
+        let vnormal = self.properties.get_vertex_property::<Vector3<f32>>("v:normal");
+        let vcolor = self.properties.get_vertex_property::<Vector3<f32>>("v:color");
+        let vquality = self.properties.get_vertex_property::<f32>("v:quality");
+        let fnormal = self.properties.get_face_property::<Vector3<f32>>("f:normal");
+        let fcolor = self.properties.get_face_property::<Vector3<f32>>("f:color");
+        let fquality = self.properties.get_face_property::<f32>("f:quality");
+        let vextra = extra_properties::<PhantomVertex>(&self.properties,"v:");
+        let fextra = extra_properties::<PhantomFace>(&self.properties,"f:");
+
+        let mut output = BufWriter::new(output);
+        try!(write!(output,"ply\n"));
+        match format {
+            PlyFormat::Ascii => try!(write!(output,"format ascii 1.0\n")),
+            PlyFormat::BinaryLittleEndian => try!(write!(output,"format binary_little_endian 1.0\n")),
+            PlyFormat::BinaryBigEndian => try!(write!(output,"format binary_big_endian 1.0\n")),
+        }
+        try!(write!(output,"element vertex {}\n",self.topology.n_vertices()));
+        for axis in &["x","y","z"] {
+            try!(write!(output,"property {} {}\n",ptype.name(),axis));
+        }
+        if vnormal.is_some() {
+            try!(write!(output,"property float nx\nproperty float ny\nproperty float nz\n"));
+        }
+        if vcolor.is_some() {
+            try!(write!(output,"property uchar red\nproperty uchar green\nproperty uchar blue\n"));
+        }
+        if vquality.is_some() {
+            try!(write!(output,"property float quality\n"));
+        }
+        for &(ref name,e) in vextra.iter() {
+            try!(write!(output,"property {} {}\n",e.ply_type().name(),name));
+        }
+        try!(write!(output,"element face {}\n",self.topology.n_faces()));
+        try!(write!(output,"property list {} int vertex_indices\n",ctype.name()));
+        if fnormal.is_some() {
+            try!(write!(output,"property float nx\nproperty float ny\nproperty float nz\n"));
+        }
+        if fcolor.is_some() {
+            try!(write!(output,"property uchar red\nproperty uchar green\nproperty uchar blue\n"));
+        }
+        if fquality.is_some() {
+            try!(write!(output,"property float quality\n"));
+        }
+        for &(ref name,e) in fextra.iter() {
+            try!(write!(output,"property {} {}\n",e.ply_type().name(),name));
+        }
+        try!(write!(output,"end_header\n"));
+
+        let mut writer = PlyWriter {
+            output_ : output,
+            format_ : format,
+            first_ : true,
+        };
+
+        // vertices are renumbered to skip the deleted ones
+        let mut vindex : Vec<usize> = vec![0;self.topology.vertices_size()];
+        for (i,v) in self.topology.vertices().enumerate() {
+            vindex[v.idx()] = i;
+            for &c in position(v).iter() {
+                try!(writer.write_value(ptype,c));
+            }
+            if let Some(p) = vnormal {
+                try!(writer.write_vector(PlyType::Float,self.properties[(p,v)],1.));
+            }
+            if let Some(p) = vcolor {
+                try!(writer.write_vector(PlyType::UChar,self.properties[(p,v)],255.));
+            }
+            if let Some(p) = vquality {
+                try!(writer.write_value(PlyType::Float,self.properties[(p,v)] as f64));
+            }
+            for &(_,e) in vextra.iter() {
+                try!(writer.write_value(e.ply_type(),e.value(&self.properties,v)));
+            }
+            try!(writer.end_element());
+        }
+
+        for f in self.topology.faces() {
+            let n = self.topology.vertices_around(f).count();
+            try!(writer.write_value(ctype,n as f64));
+            for v in self.topology.vertices_around(f) {
+                try!(writer.write_value(PlyType::Int,vindex[v.idx()] as f64));
+            }
+            if let Some(p) = fnormal {
+                try!(writer.write_vector(PlyType::Float,self.properties[(p,f)],1.));
+            }
+            if let Some(p) = fcolor {
+                try!(writer.write_vector(PlyType::UChar,self.properties[(p,f)],255.));
+            }
+            if let Some(p) = fquality {
+                try!(writer.write_value(PlyType::Float,self.properties[(p,f)] as f64));
+            }
+            for &(_,e) in fextra.iter() {
+                try!(writer.write_value(e.ply_type(),e.value(&self.properties,f)));
+            }
+            try!(writer.end_element());
+        }
+
+        try!(writer.output_.flush());
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mesh::Mesh;
+    use nalgebra::Vector3;
+    use std::str;
+
+    fn quad() -> Mesh {
+        let mut m = Mesh::new();
+        let pos = m.properties.add_vertex_property::<Vector3<f32>>("v:position",Vector3::new(0f32,0f32,0f32)).unwrap();
+        let v0 = m.add_vertex();
+        m.properties[(pos,v0)] = Vector3::new(0.,0.,0.);
+        let v1 = m.add_vertex();
+        m.properties[(pos,v1)] = Vector3::new(1.,0.,0.);
+        let v2 = m.add_vertex();
+        m.properties[(pos,v2)] = Vector3::new(0.,1.,0.);
+        let v3 = m.add_vertex();
+        m.properties[(pos,v3)] = Vector3::new(1.,1.5,0.);
+        m.add_face(&vec![v0,v1,v2]);
+        m.add_face(&vec![v2,v1,v3]);
+        m
+    }
+
+    #[test]
+    fn write_ply_ascii() {
+        let m = quad();
+        let expected =
+        r#"ply
+format ascii 1.0
+element vertex 4
+property float x
+property float y
+property float z
+element face 2
+property list uchar int vertex_indices
+end_header
+0 0 0
+1 0 0
+0 1 0
+1 1.5 0
+3 0 1 2
+3 2 1 3
+"#;
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_ply(&mut output,PlyFormat::Ascii).is_ok());
+        assert_eq!(expected,str::from_utf8(&output).unwrap());
+    }
+
+    #[test]
+    fn load_ply_ascii() {
+        let input =
+        r#"ply
+format ascii 1.0
+comment made by hand
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 2
+property list uchar int vertex_indices
+property float quality
+element edge 1
+property int vertex1
+property int vertex2
+end_header
+0 0 0 255 0 0
+1 0 0 0 255 0
+0 1 0 0 0 255
+1 1.5 0 0 0 0
+3 0 1 2 0.5
+3 2 1 3 1.5
+0 1
+"#;
+        let m = Mesh::load_ply(input.as_bytes()).ok().unwrap();
+        assert_eq!(m.topology.n_vertices(),4);
+        assert_eq!(m.topology.n_faces(),2);
+        let pos = m.properties.get_vertex_property::<Vector3<f32>>("v:position").unwrap();
+        assert_eq!(m.properties[(pos,Vertex::new(3))],Vector3::new(1.,1.5,0.));
+        let color = m.properties.get_vertex_property::<Vector3<f32>>("v:color").unwrap();
+        assert_eq!(m.properties[(color,Vertex::new(1))],Vector3::new(0.,1.,0.));
+        let quality = m.properties.get_face_property::<f32>("f:quality").unwrap();
+        assert_eq!(m.properties[(quality,Face::new(1))],1.5);
+        assert!(m.properties.get_vertex_property::<Vector3<f32>>("v:normal").is_none());
+    }
+
+    #[test]
+    fn load_write_ply_binary() {
+        let mut m = quad();
+        let normal = m.properties.add_vertex_property::<Vector3<f32>>("v:normal",Vector3::new(0.,0.,1.)).unwrap();
+        let color = m.properties.add_face_property::<Vector3<f32>>("f:color",Vector3::new(1.,0.,0.)).unwrap();
+        m.properties[(normal,Vertex::new(2))] = Vector3::new(0.,1.,0.);
+        m.properties[(color,Face::new(1))] = Vector3::new(0.,0.,1.);
+
+        for format in vec![PlyFormat::BinaryLittleEndian,PlyFormat::BinaryBigEndian] {
+            let mut output = Vec::<u8>::new();
+            assert!(m.write_ply(&mut output,format).is_ok());
+            let m2 = Mesh::load_ply(&output[..]).ok().unwrap();
+            assert_eq!(m2.topology.n_vertices(),4);
+            assert_eq!(m2.topology.n_faces(),2);
+            let pos = m2.properties.get_vertex_property::<Vector3<f32>>("v:position").unwrap();
+            assert_eq!(m2.properties[(pos,Vertex::new(3))],Vector3::new(1.,1.5,0.));
+            let normal = m2.properties.get_vertex_property::<Vector3<f32>>("v:normal").unwrap();
+            assert_eq!(m2.properties[(normal,Vertex::new(2))],Vector3::new(0.,1.,0.));
+            assert_eq!(m2.properties[(normal,Vertex::new(0))],Vector3::new(0.,0.,1.));
+            let color = m2.properties.get_face_property::<Vector3<f32>>("f:color").unwrap();
+            assert_eq!(m2.properties[(color,Face::new(0))],Vector3::new(1.,0.,0.));
+            assert_eq!(m2.properties[(color,Face::new(1))],Vector3::new(0.,0.,1.));
+        }
+    }
+
+    #[test]
+    fn write_ply_colors_round_trip() {
+        let mut m = quad();
+        let color = m.properties.add_vertex_property::<Vector3<f32>>("v:color",Vector3::new(0.,0.,0.)).unwrap();
+        m.properties[(color,Vertex::new(0))] = Vector3::new(200. / 255.,1.2,-0.1);
+        for format in vec![PlyFormat::Ascii,PlyFormat::BinaryLittleEndian] {
+            let mut output = Vec::<u8>::new();
+            assert!(m.write_ply(&mut output,format).is_ok());
+            let mut m2 = Mesh::load_ply(&output[..]).ok().unwrap();
+            let color = m2.properties.get_vertex_property::<Vector3<f32>>("v:color").unwrap();
+            let loaded = m2.properties[(color,Vertex::new(0))];
+            assert_eq!(((loaded.x * 255.).round(),loaded.y,loaded.z),(200.,1.,0.));
+            // the colors do not drift when they are written and read again
+            for _ in 0..3 {
+                let mut output = Vec::<u8>::new();
+                assert!(m2.write_ply(&mut output,format).is_ok());
+                m2 = Mesh::load_ply(&output[..]).ok().unwrap();
+            }
+            let color = m2.properties.get_vertex_property::<Vector3<f32>>("v:color").unwrap();
+            assert_eq!(m2.properties[(color,Vertex::new(0))],loaded);
+        }
+    }
+
+    #[test]
+    fn write_ply_f64() {
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f64;3]>().unwrap();
+        let v0 = m.add_vertex();
+        let v1 = m.add_vertex();
+        let v2 = m.add_vertex();
+        m.properties[(pos,v0)] = [0.1,0.,1e9 + 0.5];
+        m.properties[(pos,v1)] = [1.,0.,0.];
+        m.properties[(pos,v2)] = [0.,1.,0.];
+        m.add_face(&vec![v0,v1,v2]);
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_ply(&mut output,PlyFormat::Ascii).is_ok());
+        let text = str::from_utf8(&output).unwrap();
+        assert!(text.contains("property double x\nproperty double y\nproperty double z\n"));
+        assert!(text.contains("0.1 0 1000000000.5\n"));
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_ply(&mut output,PlyFormat::BinaryLittleEndian).is_ok());
+        let x = (1e9f64 + 0.5).to_bits().to_le_bytes();
+        assert!(output.windows(8).any(|w| w == x));
+        assert!(Mesh::load_ply(&output[..]).is_ok());
+
+        // the f64 coordinates are read back without loss
+        for &format in &[PlyFormat::Ascii,PlyFormat::BinaryBigEndian] {
+            let mut output = Vec::<u8>::new();
+            assert!(m.write_ply(&mut output,format).is_ok());
+            let m2 = Mesh::load_ply_as::<[f64;3],_>(&output[..]).ok().unwrap();
+            let pos2 = m2.positions::<[f64;3]>().unwrap();
+            for &v in &[v0,v1,v2] {
+                assert_eq!(m.properties[(pos,v)],m2.properties[(pos2,v)]);
+            }
+        }
+    }
+
+    #[test]
+    fn write_ply_large_face() {
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f32;3]>().unwrap();
+        let n = 300;
+        let vertices : Vec<Vertex> = (0..n).map(|i| {
+            let v = m.add_vertex();
+            let a = 2. * ::std::f32::consts::PI * i as f32 / n as f32;
+            m.properties[(pos,v)] = [a.cos(),a.sin(),0.];
+            v
+        }).collect();
+        m.add_face(&vertices).unwrap();
+        for &format in &[PlyFormat::Ascii,PlyFormat::BinaryBigEndian] {
+            let mut output = Vec::<u8>::new();
+            assert!(m.write_ply(&mut output,format).is_ok());
+            assert!(String::from_utf8_lossy(&output).contains("property list int int vertex_indices\n"));
+            let m2 = Mesh::load_ply(&output[..]).ok().unwrap();
+            assert_eq!(m2.topology.vertices_around(Face::new(0)).count(),n);
+        }
+    }
+
+    #[test]
+    fn write_ply_with_garbage() {
+        let mut m = quad();
+        m.delete_vertex(Vertex::new(0));
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_ply(&mut output,PlyFormat::Ascii).is_ok());
+        let m2 = Mesh::load_ply(&output[..]).ok().unwrap();
+        assert_eq!(m2.topology.n_vertices(),3);
+        assert_eq!(m2.topology.n_faces(),1);
+    }
+
+    #[test]
+    fn load_write_ply_extra_properties() {
+        let input =
+        r#"ply
+format ascii 1.0
+element vertex 3
+property float x
+property float y
+property float z
+property float confidence
+property double weight
+element face 1
+property list uchar int vertex_indices
+property ushort label
+property uint id
+end_header
+0 0 0 0.5 0.25
+1 0 0 1 0.5
+0 1 0 0 0.75
+3 0 1 2 7 4294967295
+"#;
+        let m = Mesh::load_ply(input.as_bytes()).ok().unwrap();
+        let confidence = m.properties.get_vertex_property::<f32>("v:confidence").unwrap();
+        let weight = m.properties.get_vertex_property::<f64>("v:weight").unwrap();
+        let label = m.properties.get_face_property::<u16>("f:label").unwrap();
+        let id = m.properties.get_face_property::<u32>("f:id").unwrap();
+        assert_eq!(m.properties[(confidence,Vertex::new(1))],1.);
+        assert_eq!(m.properties[(weight,Vertex::new(2))],0.75);
+        assert_eq!(m.properties[(label,Face::new(0))],7);
+        assert_eq!(m.properties[(id,Face::new(0))],4294967295);
+
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_ply(&mut output,PlyFormat::BinaryLittleEndian).is_ok());
+        let m2 = Mesh::load_ply(&output[..]).ok().unwrap();
+        let confidence = m2.properties.get_vertex_property::<f32>("v:confidence").unwrap();
+        let weight = m2.properties.get_vertex_property::<f64>("v:weight").unwrap();
+        let label = m2.properties.get_face_property::<u16>("f:label").unwrap();
+        let id = m2.properties.get_face_property::<u32>("f:id").unwrap();
+        assert_eq!(m2.properties[(confidence,Vertex::new(0))],0.5);
+        assert_eq!(m2.properties[(weight,Vertex::new(2))],0.75);
+        assert_eq!(m2.properties[(label,Face::new(0))],7);
+        assert_eq!(m2.properties[(id,Face::new(0))],4294967295);
+
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_ply(&mut output,PlyFormat::Ascii).is_ok());
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("property ushort label\nproperty uint id\n"));
+        assert!(text.contains("3 0 1 2 7 4294967295\n"));
+    }
+
+    #[test]
+    fn load_ply_invalid() {
+        match Mesh::load_ply("ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nend_header\n0\n".as_bytes()) {
+            Err(MeshLoadingError::MissingPosition) => {},
+            _ => assert!(false),
+        }
+        match Mesh::load_ply("off\n".as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        let input = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+                     element face 1\nproperty list uchar int vertex_indices\nend_header\n0 0 0\n1 0 0\n0 1 0\n3 0 1\n";
+        match Mesh::load_ply(input.as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        let input = "ply\nformat ascii 1.0\nelement vertex 18446744073709551615\nproperty float x\nproperty float y\nproperty float z\n\
+                     end_header\n0 0 0\n";
+        match Mesh::load_ply(input.as_bytes()) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        for indices in &["3 0 1 -1","3 0 1 1.5"] {
+            let input = format!("ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+                                 element face 1\nproperty list uchar float vertex_indices\nend_header\n0 0 0\n1 0 0\n0 1 0\n{}\n",indices);
+            match Mesh::load_ply(input.as_bytes()) {
+                Err(MeshLoadingError::InvalidFormat(_)) => {},
+                _ => assert!(false),
+            }
+        }
+    }
+}
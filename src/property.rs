@@ -1,14 +1,21 @@
 use std::marker::PhantomData;
 use std::any::Any;
+use std::any::type_name;
 use handle::*;
 use std::ops::Index;
 use std::ops::IndexMut;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 pub trait ResizableVec {
     fn len(&self) -> usize;
     fn reserve(&mut self, size : usize);
     fn capacity(&self) -> usize;
     fn push(&mut self);
+    fn swap(&mut self, i0 : usize, i1 : usize);
+    fn copy(&mut self, from : usize, to : usize);
+    fn resize(&mut self, size : usize);
+    fn type_name(&self) -> &'static str;
+    fn clone_box(&self) -> Box<ResizableVec>;
     fn as_any(&self) -> &Any;
     fn as_any_mut(&mut self) -> &mut Any;
 }
@@ -29,6 +36,16 @@ impl<T : 'static, D : 'static> PropertyVec<Handle<T>, D> {
     }
 }
 
+impl<H, D : Clone> Clone for PropertyVec<H, D> {
+    fn clone(&self) -> PropertyVec<H, D> {
+        PropertyVec {
+            default_ : self.default_.clone(),
+            data_ : self.data_.clone(),
+            handle_ : PhantomData,
+        }
+    }
+}
+
 impl<T : 'static, D : Clone> ResizableVec for PropertyVec<Handle<T>, D> {
     fn reserve(&mut self, size : usize) {
         self.data_.reserve(size);
@@ -46,6 +63,27 @@ impl<T : 'static, D : Clone> ResizableVec for PropertyVec<Handle<T>, D> {
         self.data_.push(self.default_.clone());
     }
 
+    fn swap(&mut self, i0 : usize, i1 : usize) {
+        self.data_.swap(i0,i1);
+    }
+
+    fn copy(&mut self, from : usize, to : usize) {
+        self.data_[to] = self.data_[from].clone();
+    }
+
+    fn resize(&mut self, size : usize) {
+        let default_value = self.default_.clone();
+        self.data_.resize(size,default_value);
+    }
+
+    fn type_name(&self) -> &'static str {
+        type_name::<D>()
+    }
+
+    fn clone_box(&self) -> Box<ResizableVec> {
+        Box::new(self.clone())
+    }
+
     fn as_any(&self) -> &Any {
         self
     }
@@ -69,12 +107,38 @@ impl<T,D> IndexMut<Handle<T>> for PropertyVec<Handle<T>, D> {
     }
 }
 
+/// The errors of the accesses to a property through a `PropertyHandle`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PropertyError {
+    /// The handle was created by another container, for instance the one of another mesh.
+    WrongContainer,
+    /// The property was removed.
+    Removed,
+    /// The property does not store the type of the handle.
+    WrongType,
+    /// There is no element with this index.
+    OutOfBounds(usize),
+}
+
+/// The id of the next created `PropertyContainer`.
+static NEXT_CONTAINER_ID : AtomicUsize = AtomicUsize::new(1);
+
+/// The generation of the next added property, unique across all the containers and their clones.
+static NEXT_GENERATION : AtomicUsize = AtomicUsize::new(0);
+
 /// A growable list type, to store data for the different `Handle`.
 ///
 /// `H` is the type of the Handle to acces the data.
+///
+/// Properties are looked up by name only by `add` and `get`, the returned handles index the
+/// arrays directly. Removed properties leave an empty slot, so the handles of the other properties stay valid.
+/// Empty slots are reused by the next added properties. Every added property gets a new generation,
+/// so that the handles of the removed property are rejected, even by a clone of the container.
 pub struct PropertyContainer<H> {
     handle_ : PhantomData<H>,
-    pub parrays_ : Vec<(& 'static str,Box<ResizableVec>)>,
+    pub parrays_ : Vec<Option<(String,Box<ResizableVec>)>>,
+    generations_ : Vec<usize>,
+    id_ : usize,
     size_ : usize,
     capacity_ : usize,
 }
@@ -85,6 +149,8 @@ impl<T : 'static> PropertyContainer<Handle<T>> {
         PropertyContainer {
             handle_ : PhantomData,
             parrays_ : Vec::new(),
+            generations_ : Vec::new(),
+            id_ : NEXT_CONTAINER_ID.fetch_add(1,Ordering::Relaxed),
             size_ : 0,
             capacity_ : 0
         }
@@ -93,17 +159,20 @@ impl<T : 'static> PropertyContainer<Handle<T>> {
     /// Reserve the minimun capacity to store at least `size` elements in the given `PropertyContainer`.
     pub fn reserve(&mut self, size : usize) {
         self.capacity_ = size;
-        for &mut(_, ref mut b) in self.parrays_.iter_mut() {
+        for &mut(_, ref mut b) in self.parrays_.iter_mut().flat_map(|p| p.as_mut()) {
             b.reserve(size);
         }
     }
 
+    /// Returns the handle of the property at index `i`.
+    fn handle<D>(&self, i : usize) -> PropertyHandle<(T,D)> {
+        PropertyHandle::<(T,D)>::new(i,self.id_,self.generations_[i])
+    }
+
     /// Add a property with default value. If a property with this name already exists, return `None`.
-    pub fn add<D : 'static + Clone>(&mut self, name : & 'static str, default_value : D) -> Option<Handle<(T,D)> > {
-        for &(n, _) in self.parrays_.iter() {
-            if n == name {
-                return None;
-            }
+    pub fn add<D : 'static + Clone>(&mut self, name : &str, default_value : D) -> Option<PropertyHandle<(T,D)> > {
+        if self.find(name).is_some() {
+            return None;
         }
         let mut gv = PropertyVec::<Handle<T>,D>::new(default_value);
         gv.reserve(self.capacity_);
@@ -111,47 +180,238 @@ impl<T : 'static> PropertyContainer<Handle<T>> {
             gv.push();
         }
         let p = Box::new(gv);
-        self.parrays_.push((name,p));
-        return Some(Handle::<(T,D)>::new(self.parrays_.len()-1));
+        let generation = NEXT_GENERATION.fetch_add(1,Ordering::Relaxed);
+        match self.parrays_.iter().position(|p| p.is_none()) {
+            Some(i) => {
+                self.parrays_[i] = Some((String::from(name),p));
+                self.generations_[i] = generation;
+                return Some(self.handle(i));
+            },
+            None => {
+                self.parrays_.push(Some((String::from(name),p)));
+                self.generations_.push(generation);
+                return Some(self.handle(self.parrays_.len()-1));
+            },
+        }
     }
 
     /// Get a property by its name. If it does not exist, return `None`.
-    pub fn get<D : 'static + Clone>(&self, name : & 'static str) -> Option<Handle<(T,D)> > {
-        for (i, &(n, ref b)) in self.parrays_.iter().enumerate() {
-            if n == name && b.as_any().downcast_ref::<PropertyVec<Handle<T>,D>>().is_some() {
-                return Some(Handle::<(T,D)>::new(i));
-            }
+    pub fn get<D : 'static + Clone>(&self, name : &str) -> Option<PropertyHandle<(T,D)> > {
+        match self.find(name) {
+            Some(i) => {
+                let &(_, ref b) = self.parrays_[i].as_ref().unwrap();
+                if b.as_any().downcast_ref::<PropertyVec<Handle<T>,D>>().is_some() {
+                    return Some(self.handle(i));
+                }
+                return None;
+            },
+            None => return None,
+        }
+    }
+
+    /// Returns the index of the property named `name`, if any.
+    fn find(&self, name : &str) -> Option<usize> {
+        self.parrays_.iter().position(|p| match *p {
+            Some((ref n, _)) => n == name,
+            None => false,
+        })
+    }
+
+    /// Checks that the property handle `p` was created by this container and is still valid.
+    fn check<D>(&self, p : PropertyHandle<(T,D)>) -> Result<&Box<ResizableVec>,PropertyError> {
+        if p.container() != self.id_ {
+            return Err(PropertyError::WrongContainer);
+        }
+        match self.parrays_.get(p.idx()) {
+            Some(&Some((_, ref b))) if self.generations_[p.idx()] == p.generation() => Ok(b),
+            _ => Err(PropertyError::Removed),
+        }
+    }
+
+    /// Returns the array of the property `p`.
+    fn array<D : 'static>(&self, p : PropertyHandle<(T,D)>) -> Result<&PropertyVec<Handle<T>,D>,PropertyError> {
+        let b = try!(self.check(p));
+        match b.as_any().downcast_ref::<PropertyVec<Handle<T>,D>>() {
+            Some(pa) => Ok(pa),
+            None => Err(PropertyError::WrongType),
+        }
+    }
+
+    /// Returns the mutable array of the property `p`.
+    fn array_mut<D : 'static>(&mut self, p : PropertyHandle<(T,D)>) -> Result<&mut PropertyVec<Handle<T>,D>,PropertyError> {
+        try!(self.check(p));
+        let &mut (_, ref mut b) = self.parrays_[p.idx()].as_mut().unwrap();
+        match b.as_any_mut().downcast_mut::<PropertyVec<Handle<T>,D>>() {
+            Some(pa) => Ok(pa),
+            None => Err(PropertyError::WrongType),
+        }
+    }
+
+    /// Returns the elements of the property `p`, indexed by the handle indices.
+    pub fn slice<D : 'static>(&self, p : PropertyHandle<(T,D)>) -> Result<&[D],PropertyError> {
+        self.array(p).map(|pa| &pa.data_[..])
+    }
+
+    /// Returns the mutable elements of the property `p`, indexed by the handle indices.
+    pub fn slice_mut<D : 'static>(&mut self, p : PropertyHandle<(T,D)>) -> Result<&mut [D],PropertyError> {
+        self.array_mut(p).map(|pa| &mut pa.data_[..])
+    }
+
+    /// Access the element `h` of the property `p`, or return why the access is invalid.
+    pub fn try_get<D : 'static>(&self, p : PropertyHandle<(T,D)>, h : Handle<T>) -> Result<&D,PropertyError> {
+        let pa = try!(self.array(p));
+        match pa.data_.get(h.idx()) {
+            Some(x) => Ok(x),
+            None => Err(PropertyError::OutOfBounds(h.idx())),
+        }
+    }
+
+    /// Mutable access to the element `h` of the property `p`, or return why the access is invalid.
+    pub fn try_get_mut<D : 'static>(&mut self, p : PropertyHandle<(T,D)>, h : Handle<T>) -> Result<&mut D,PropertyError> {
+        let pa = try!(self.array_mut(p));
+        match pa.data_.get_mut(h.idx()) {
+            Some(x) => Ok(x),
+            None => Err(PropertyError::OutOfBounds(h.idx())),
+        }
+    }
+
+    /// Remove the property `p`. If it was already removed, return `false`.
+    pub fn remove<D : 'static>(&mut self, p : PropertyHandle<(T,D)>) -> bool {
+        if self.array(p).is_err() {
+            return false;
+        }
+        self.parrays_[p.idx()] = None;
+        true
+    }
+
+    /// Rename the property `old_name` into `new_name`.
+    /// If `old_name` does not exist or `new_name` already exists, return `false`.
+    pub fn rename(&mut self, old_name : &str, new_name : &str) -> bool {
+        if self.find(new_name).is_some() {
+            return false;
+        }
+        match self.find(old_name) {
+            Some(i) => {
+                self.parrays_[i].as_mut().unwrap().0 = String::from(new_name);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Sets all the elements of the property `name` to its default value.
+    /// If `name` does not exist, return `false`.
+    pub fn reset(&mut self, name : &str) -> bool {
+        match self.find(name) {
+            Some(i) => {
+                let &mut (_, ref mut b) = self.parrays_[i].as_mut().unwrap();
+                b.resize(0);
+                b.resize(self.size_);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Returns the names of all existing properties.
+    pub fn names(&self) -> Vec<&str> {
+        self.parrays_.iter().flat_map(|p| p.as_ref()).map(|&(ref n, _)| n.as_str()).collect()
+    }
+
+    /// Returns the name of the type stored in the property `name`. If it does not exist, return `None`.
+    pub fn type_name(&self, name : &str) -> Option<& 'static str> {
+        self.find(name).map(|i| self.parrays_[i].as_ref().unwrap().1.type_name())
+    }
+
+    /// Returns a copy of the given `PropertyContainer` keeping only the properties whose name is in `names`.
+    ///
+    /// The handles of the kept properties remain valid on the copy.
+    pub fn clone_subset(&self, names : &[&str]) -> PropertyContainer<Handle<T>> {
+        // the removed properties leave an empty slot so that the kept handles keep their index
+        let parrays = self.parrays_.iter().map(|p| match *p {
+            Some((ref n, ref b)) if names.contains(&n.as_str()) => Some((n.clone(),b.clone_box())),
+            _ => None,
+        }).collect();
+        PropertyContainer {
+            handle_ : PhantomData,
+            parrays_ : parrays,
+            generations_ : self.generations_.clone(),
+            id_ : self.id_,
+            size_ : self.size_,
+            capacity_ : self.capacity_,
         }
-        return None;
     }
 
     /// Adds a new element to all existing Property.
     pub fn push(&mut self){
         self.size_ += 1;
-        for &mut(_, ref mut b) in self.parrays_.iter_mut() {
+        for &mut(_, ref mut b) in self.parrays_.iter_mut().flat_map(|p| p.as_mut()) {
             b.push();
         }
     }
+
+    /// Swaps the elements `i0` and `i1` in all existing Property.
+    pub fn swap(&mut self, i0 : usize, i1 : usize) {
+        for &mut(_, ref mut b) in self.parrays_.iter_mut().flat_map(|p| p.as_mut()) {
+            b.swap(i0,i1);
+        }
+    }
+
+    /// Copies the element `from` over the element `to` in all existing Property.
+    pub fn copy(&mut self, from : usize, to : usize) {
+        for &mut(_, ref mut b) in self.parrays_.iter_mut().flat_map(|p| p.as_mut()) {
+            b.copy(from,to);
+        }
+    }
+
+    /// Resizes all existing Property to `size` elements.
+    pub fn resize(&mut self, size : usize) {
+        self.size_ = size;
+        for &mut(_, ref mut b) in self.parrays_.iter_mut().flat_map(|p| p.as_mut()) {
+            b.resize(size);
+        }
+    }
 }
 
-impl<T : 'static, D : 'static> Index<(Handle<(T,D)>,Handle<T>)> for PropertyContainer<Handle<T>> {
+/// The copy keeps the id of the container, so that the handles of the existing properties are valid on both.
+/// The properties added afterwards get distinct generations, so their handles are only valid on their own container.
+impl<H> Clone for PropertyContainer<H> {
+    fn clone(&self) -> PropertyContainer<H> {
+        PropertyContainer {
+            handle_ : PhantomData,
+            parrays_ : self.parrays_.iter().map(|p| p.as_ref().map(|&(ref n, ref b)| (n.clone(),b.clone_box()))).collect(),
+            generations_ : self.generations_.clone(),
+            id_ : self.id_,
+            size_ : self.size_,
+            capacity_ : self.capacity_,
+        }
+    }
+}
+
+impl<T : 'static, D : 'static> Index<(PropertyHandle<(T,D)>,Handle<T>)> for PropertyContainer<Handle<T>> {
     type Output = D;
 
     /// Access the element of the vertex 'Property' prop indexing by 'Vertex' v.
-    fn index(&self, (p,h): (Handle<(T,D)>,Handle<T>)) -> &D {
-        let (_,ref b) = self.parrays_[p.idx()];
-        let pa : &PropertyVec<Handle<T>,D> = b.as_any().downcast_ref::<PropertyVec<Handle<T>,D>>().unwrap();
-        return &pa[h];
+    ///
+    /// Panics if the property handle is invalid or the element does not exist.
+    fn index(&self, (p,h): (PropertyHandle<(T,D)>,Handle<T>)) -> &D {
+        match self.try_get(p,h) {
+            Ok(x) => x,
+            Err(e) => panic!("invalid property access: {:?}",e),
+        }
     }
 }
 
-impl<T : 'static, D : 'static> IndexMut<(Handle<(T,D)>,Handle<T>)> for PropertyContainer<Handle<T>> {
+impl<T : 'static, D : 'static> IndexMut<(PropertyHandle<(T,D)>,Handle<T>)> for PropertyContainer<Handle<T>> {
 
     /// Mutable access to the element of the vertex 'Property' prop indexing by 'Vertex' v.
-    fn index_mut(&mut self, (p,h): (Handle<(T,D)>,Handle<T>)) -> &mut D {
-        let (_,ref mut b) = self.parrays_[p.idx()];
-        let pa : &mut PropertyVec<Handle<T>,D> = b.as_any_mut().downcast_mut::<PropertyVec<Handle<T>,D>>().unwrap();
-        return &mut pa[h];
+    ///
+    /// Panics if the property handle is invalid or the element does not exist.
+    fn index_mut(&mut self, (p,h): (PropertyHandle<(T,D)>,Handle<T>)) -> &mut D {
+        match self.try_get_mut(p,h) {
+            Ok(x) => x,
+            Err(e) => panic!("invalid property access: {:?}",e),
+        }
     }
 }
 
@@ -218,7 +478,7 @@ mod tests {
         pcontainer.reserve(17);
         assert!(17 <= pcontainer.capacity_);
         let prop = pcontainer.add::<u32>("v:my_prop",0).unwrap();
-        let (_,ref b) = pcontainer.parrays_[prop.idx()];
+        let &(_,ref b) = pcontainer.parrays_[prop.idx()].as_ref().unwrap();
         let pa = b.as_any().downcast_ref::<PropertyVec<Vertex,u32>>().unwrap();
         assert_eq!(pa.capacity(),17);
     }
@@ -237,6 +497,41 @@ mod tests {
         assert_eq!(pcontainer[(prop,v1)],17);
     }
 
+    #[test]
+    fn swap_and_resize() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let prop = pcontainer.add::<u32>("v:my_prop",17).unwrap();
+        for _ in 0..3 {
+            pcontainer.push();
+        }
+        let v0 = Vertex::new(0);
+        let v2 = Vertex::new(2);
+        pcontainer[(prop,v0)] = 42;
+        pcontainer.swap(0,2);
+        assert_eq!(pcontainer[(prop,v0)],17);
+        assert_eq!(pcontainer[(prop,v2)],42);
+        pcontainer.resize(1);
+        assert!(pcontainer.size_ == 1);
+        pcontainer.push();
+        assert_eq!(pcontainer[(prop,Vertex::new(1))],17);
+    }
+
+    #[test]
+    fn copy() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let prop = pcontainer.add::<u32>("v:my_prop",17).unwrap();
+        let name = pcontainer.add::<String>("v:name",String::new()).unwrap();
+        for _ in 0..3 {
+            pcontainer.push();
+        }
+        pcontainer[(prop,Vertex::new(0))] = 42;
+        pcontainer[(name,Vertex::new(0))] = "v0".to_string();
+        pcontainer.copy(0,2);
+        assert_eq!(pcontainer[(prop,Vertex::new(2))],42);
+        assert_eq!(pcontainer[(name,Vertex::new(2))],"v0");
+        assert_eq!(pcontainer[(prop,Vertex::new(1))],17);
+    }
+
     #[test]
     fn access() {
         let mut pcontainer = PropertyContainer::<Vertex>::new();
@@ -248,6 +543,156 @@ mod tests {
         assert_eq!(pcontainer[(prop,v)],42);
     }
 
+    #[test]
+    fn remove() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let p0 = pcontainer.add::<u32>("v:p0",17).unwrap();
+        let p1 = pcontainer.add::<f32>("v:p1",0.5).unwrap();
+        pcontainer.push();
+        let v = Vertex::new(0);
+        assert!(pcontainer.remove(p0));
+        assert!(!pcontainer.remove(p0));
+        assert!(pcontainer.get::<u32>("v:p0").is_none());
+        assert_eq!(pcontainer[(p1,v)],0.5);
+        let p2 = pcontainer.add::<u32>("v:p2",3).unwrap();
+        assert_eq!(p2.idx(),p0.idx());
+        assert_eq!(pcontainer[(p2,v)],3);
+        pcontainer.push();
+        assert_eq!(pcontainer[(p2,Vertex::new(1))],3);
+    }
+
+    #[test]
+    fn remove_wrong_type() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let p = pcontainer.add::<u32>("v:my_prop",17).unwrap();
+        assert!(!pcontainer.remove(PropertyHandle::<(PhantomVertex,f32)>::new(p.idx(),pcontainer.id_,p.generation())));
+        assert!(pcontainer.get::<u32>("v:my_prop").is_some());
+    }
+
+    #[test]
+    fn rename_and_names() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let p = pcontainer.add::<u32>("v:p0",17).unwrap();
+        pcontainer.add::<f32>("v:p1",0.5).unwrap();
+        assert!(!pcontainer.rename("v:p0","v:p1"));
+        assert!(!pcontainer.rename("v:p2","v:p3"));
+        assert!(pcontainer.rename("v:p0","v:p2"));
+        assert_eq!(pcontainer.get::<u32>("v:p2").unwrap().idx(),p.idx());
+        assert_eq!(pcontainer.names(),vec!["v:p2","v:p1"]);
+        assert_eq!(pcontainer.type_name("v:p2"),Some("u32"));
+        assert_eq!(pcontainer.type_name("v:p1"),Some("f32"));
+        assert_eq!(pcontainer.type_name("v:p0"),None);
+    }
+
+    #[test]
+    fn runtime_names() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let names : Vec<String> = (0..3).map(|i| format!("v:prop{}",i)).collect();
+        for (i,name) in names.iter().enumerate() {
+            let prop = pcontainer.add::<usize>(name,i).unwrap();
+            assert_eq!(prop.idx(),i);
+        }
+        pcontainer.push();
+        let prop = pcontainer.get::<usize>(&names[2]).unwrap();
+        assert_eq!(pcontainer[(prop,Vertex::new(0))],2);
+        assert_eq!(pcontainer.names(),vec!["v:prop0","v:prop1","v:prop2"]);
+    }
+
+    #[test]
+    fn try_get() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let mut other = PropertyContainer::<Vertex>::new();
+        let p0 = pcontainer.add::<u32>("v:p0",17).unwrap();
+        let q = other.add::<u32>("v:p0",17).unwrap();
+        pcontainer.push();
+        other.push();
+        let v = Vertex::new(0);
+        assert_eq!(pcontainer.try_get(p0,v),Ok(&17));
+        *pcontainer.try_get_mut(p0,v).unwrap() = 42;
+        assert_eq!(pcontainer.try_get(p0,v),Ok(&42));
+        assert_eq!(pcontainer.try_get(p0,Vertex::new(1)),Err(PropertyError::OutOfBounds(1)));
+        assert_eq!(pcontainer.try_get(q,v),Err(PropertyError::WrongContainer));
+        let wrong = PropertyHandle::<(PhantomVertex,f32)>::new(p0.idx(),pcontainer.id_,p0.generation());
+        assert_eq!(pcontainer.try_get(wrong,v),Err(PropertyError::WrongType));
+    }
+
+    #[test]
+    fn stale_handle() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let p0 = pcontainer.add::<u32>("v:p0",17).unwrap();
+        pcontainer.push();
+        pcontainer.remove(p0);
+        let p1 = pcontainer.add::<u32>("v:p1",3).unwrap();
+        assert_eq!(p0.idx(),p1.idx());
+        let v = Vertex::new(0);
+        assert_eq!(pcontainer.try_get(p0,v),Err(PropertyError::Removed));
+        assert!(pcontainer.try_get_mut(p0,v).is_err());
+        assert!(!pcontainer.remove(p0));
+        assert_eq!(pcontainer[(p1,v)],3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn access_other_container() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let mut other = PropertyContainer::<Vertex>::new();
+        pcontainer.add::<u32>("v:my_prop",17).unwrap();
+        let prop = other.add::<u32>("v:my_prop",17).unwrap();
+        pcontainer.push();
+        pcontainer[(prop,Vertex::new(0))];
+    }
+
+    #[test]
+    fn clone() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let p0 = pcontainer.add::<u32>("v:p0",17).unwrap();
+        let p1 = pcontainer.add::<String>("v:p1",String::from("a")).unwrap();
+        pcontainer.push();
+        let v = Vertex::new(0);
+        let mut copy = pcontainer.clone();
+        copy[(p0,v)] = 42;
+        copy[(p1,v)].push('b');
+        assert_eq!(pcontainer[(p0,v)],17);
+        assert_eq!(pcontainer[(p1,v)],"a");
+        assert_eq!(copy[(p0,v)],42);
+        assert_eq!(copy[(p1,v)],"ab");
+        copy.push();
+        assert_eq!(copy[(p1,Vertex::new(1))],"a");
+    }
+
+    #[test]
+    fn diverging_clones() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let p0 = pcontainer.add::<u32>("v:p0",17).unwrap();
+        pcontainer.push();
+        pcontainer.remove(p0);
+        let mut copy = pcontainer.clone();
+        let p1 = pcontainer.add::<u32>("v:p1",3).unwrap();
+        let q1 = copy.add::<u32>("v:q1",5).unwrap();
+        assert_eq!(p1.idx(),q1.idx());
+        let v = Vertex::new(0);
+        assert_eq!(copy.try_get(p1,v),Err(PropertyError::Removed));
+        assert_eq!(pcontainer.try_get(q1,v),Err(PropertyError::Removed));
+        assert_eq!(pcontainer[(p1,v)],3);
+        assert_eq!(copy[(q1,v)],5);
+    }
+
+    #[test]
+    fn clone_subset() {
+        let mut pcontainer = PropertyContainer::<Vertex>::new();
+        let p0 = pcontainer.add::<u32>("v:p0",17).unwrap();
+        let p1 = pcontainer.add::<u32>("v:p1",3).unwrap();
+        pcontainer.push();
+        let v = Vertex::new(0);
+        let mut copy = pcontainer.clone_subset(&["v:p1"]);
+        assert_eq!(copy.names(),vec!["v:p1"]);
+        assert_eq!(copy.try_get(p0,v),Err(PropertyError::Removed));
+        assert_eq!(copy[(p1,v)],3);
+        let p2 = copy.add::<u32>("v:p2",5).unwrap();
+        assert_eq!(p2.idx(),p0.idx());
+        assert_eq!(copy.try_get(p0,v),Err(PropertyError::Removed));
+    }
+
     #[test]
     #[should_panic]
     fn access_out_of_bound() {
@@ -0,0 +1,380 @@
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::collections::HashMap;
+use mesh::*;
+use handle::*;
+use mesh_io::*;
+use nalgebra::Vector3;
+use geometry::{Point, Scalar};
+use mesh_iterator::VerticesAround;
+
+/// The encoding of the data stored in a STL file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StlFormat {
+    Ascii,
+    Binary,
+}
+
+/// Merges the vertices of a triangle soup which are closer than a tolerance,
+/// using a regular grid whose cells have the size of the tolerance.
+struct VertexWelder {
+    tolerance_ : f32,
+    cells_ : HashMap<(i64,i64,i64),Vec<Vertex>>,
+    points_ : Vec<Vector3<f32>>,
+}
+
+impl VertexWelder {
+    fn new(tolerance : f32) -> VertexWelder {
+        VertexWelder {
+            tolerance_ : tolerance.max(0.),
+            cells_ : HashMap::new(),
+            points_ : Vec::new(),
+        }
+    }
+
+    fn cell(&self, p : &Vector3<f32>) -> (i64,i64,i64) {
+        if self.tolerance_ > 0. {
+            let t = self.tolerance_;
+            ((p.x/t).floor() as i64, (p.y/t).floor() as i64, (p.z/t).floor() as i64)
+        } else {
+            // adding 0 turns -0 into +0
+            ((p.x+0.).to_bits() as i64, (p.y+0.).to_bits() as i64, (p.z+0.).to_bits() as i64)
+        }
+    }
+
+    /// Returns the `Vertex` at position p, adding it to the `Mesh` if no vertex is close enough.
+    fn vertex<P : Point>(&mut self, m : &mut Mesh, pos : PropertyVertex<P>, p : Vector3<f32>) -> Vertex {
+        let (i,j,k) = self.cell(&p);
+        let r = if self.tolerance_ > 0. {1} else {0};
+        for di in -r..r+1 {
+            for dj in -r..r+1 {
+                for dk in -r..r+1 {
+                    if let Some(vvec) = self.cells_.get(&(i+di,j+dj,k+dk)) {
+                        for &v in vvec.iter() {
+                            let d = self.points_[v.idx()] - p;
+                            if d.x.abs() <= self.tolerance_ && d.y.abs() <= self.tolerance_ && d.z.abs() <= self.tolerance_ {
+                                return v;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let v = m.add_vertex();
+        m.properties[(pos,v)] = P::from_coords(Scalar::from_f64(p.x as f64),Scalar::from_f64(p.y as f64),Scalar::from_f64(p.z as f64));
+        self.points_.push(p);
+        self.cells_.entry((i,j,k)).or_insert(Vec::new()).push(v);
+        v
+    }
+}
+
+/// Returns the size of a binary STL file with the triangle count of the header of `data`,
+/// or `None` if `data` has no header or the size overflows.
+fn binary_size(data : &[u8]) -> Option<usize> {
+    if data.len() < 84 {
+        return None;
+    }
+    let n = u32::from_le_bytes([data[80],data[81],data[82],data[83]]) as usize;
+    n.checked_mul(50).and_then(|x| x.checked_add(84))
+}
+
+fn read_f32(b : &[u8]) -> f32 {
+    f32::from_bits(u32::from_le_bytes([b[0],b[1],b[2],b[3]]))
+}
+
+/// Reads the triangles of an ASCII STL file.
+fn read_ascii(data : &[u8]) -> Result<Vec<Vector3<f32>>,MeshLoadingError> {
+    let text = match ::std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(_) => return invalid(String::from("invalid STL text")),
+    };
+    let mut points : Vec<Vector3<f32>> = Vec::new();
+    let mut tokens = text.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token == "vertex" {
+            let mut p = [0f32;3];
+            for i in 0..3 {
+                p[i] = match tokens.next().map(|t| t.parse::<f32>()) {
+                    Some(Ok(x)) => x,
+                    _ => return invalid(String::from("invalid STL vertex")),
+                };
+            }
+            points.push(Vector3::new(p[0],p[1],p[2]));
+        }
+    }
+    if points.len() % 3 != 0 {
+        return invalid(String::from("invalid STL facet"));
+    }
+    Ok(points)
+}
+
+/// Reads the triangles of a binary STL file.
+fn read_binary(data : &[u8]) -> Result<Vec<Vector3<f32>>,MeshLoadingError> {
+    if data.len() < 84 {
+        return invalid(String::from("truncated STL header"));
+    }
+    let n = match binary_size(data) {
+        Some(size) if data.len() >= size => (size - 84) / 50,
+        _ => return invalid(String::from("truncated STL data")),
+    };
+    let mut points : Vec<Vector3<f32>> = Vec::with_capacity(3*n);
+    for i in 0..n {
+        // skip the normal, the three vertices follow
+        let facet = &data[84+50*i+12..84+50*i+48];
+        for j in 0..3 {
+            let b = &facet[12*j..12*j+12];
+            points.push(Vector3::new(read_f32(&b[0..4]),read_f32(&b[4..8]),read_f32(&b[8..12])));
+        }
+    }
+    Ok(points)
+}
+
+/// Returns the unit normal of the triangle (a,b,c), or a null vector if it is degenerate.
+fn triangle_normal(a : Vector3<f32>, b : Vector3<f32>, c : Vector3<f32>) -> Vector3<f32> {
+    let n = (b-a).cross(&(c-a));
+    let l = n.norm();
+    if l > 0. {n / l} else {n}
+}
+
+impl Mesh {
+    /// Load Stl `Mesh` from any `Read`, the format (ASCII or binary) is detected automatically.
+    ///
+    /// STL files store each triangle independently, the vertices closer than `tolerance` in
+    /// each coordinate are merged into a single `Vertex`. A null or negative tolerance only merges
+    /// vertices with identical coordinates. Degenerate triangles are skipped, as well as the
+    /// triangles which would make the mesh non-manifold, whose vertices are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::Mesh;
+    ///
+    /// let input = "solid t\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\n\
+    ///              endloop\nendfacet\nfacet normal 0 0 1\nouter loop\nvertex 0 1 0\nvertex 1 0 0\n\
+    ///              vertex 1 1 0\nendloop\nendfacet\nendsolid t\n";
+    /// let mut m = Mesh::load_stl(input.as_bytes(),0.).ok().unwrap();
+    /// assert_eq!(m.topology.n_vertices(),4);
+    /// assert_eq!(m.topology.n_faces(),2);
+    /// ```
+    pub fn load_stl<R : io::Read>(input : R, tolerance : f32) -> Result<Mesh,MeshLoadingError> {
+        Mesh::load_stl_as::<Vector3<f32>,R>(input,tolerance)
+    }
+
+    /// Load Stl `Mesh` from any `Read`, storing the vertex positions as `P`.
+    ///
+    /// STL files store single precision coordinates, they are converted to `P` as they are.
+    pub fn load_stl_as<P : Point, R : io::Read>(mut input : R, tolerance : f32) -> Result<Mesh,MeshLoadingError> {
+        let mut data : Vec<u8> = Vec::new();
+        try!(input.read_to_end(&mut data));
+
+        // an ASCII file may not start with `solid`, but a binary one may
+        let points = if data.starts_with(b"solid") && binary_size(&data) != Some(data.len()) {
+            try!(read_ascii(&data))
+        } else {
+            try!(read_binary(&data))
+        };
+
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<P>().unwrap();
+        let mut welder = VertexWelder::new(tolerance);
+        m.face_reserve(points.len()/3);
+        for t in points.chunks(3) {
+            let mut vvec : Vec<Vertex> = t.iter().map(|&p| welder.vertex(&mut m,pos,p)).collect();
+            if vvec[0] == vvec[1] || vvec[1] == vvec[2] || vvec[2] == vvec[0] {
+                continue;
+            }
+            match add_loaded_face(&mut m,&mut vvec) {
+                Ok(_) | Err(MeshLoadingError::NotManifold) => (),
+                Err(e) => return Err(e),
+            }
+        }
+        return Ok(m);
+    }
+
+    /// Write Stl `Mesh` into any `Write`.
+    ///
+    /// Each face is written with its normal, faces which are not triangles are split in a fan of triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::load("cube.obj").ok().unwrap();
+    /// let mut output = Vec::<u8>::new();
+    /// assert!(m.write_stl(&mut output,StlFormat::Binary).is_ok());
+    /// assert_eq!(output.len(),84+12*50);
+    /// ```
+    pub fn write_stl<W : io::Write>(&self, output : W, format : StlFormat) -> Result<(),MeshLoadingError> {
+        let position = match self.coords_reader() {
+            Some(prop) => prop,
+            None => return Err(MeshLoadingError::MissingPosition),
+        };
+
+        let mut triangles : Vec<[Vector3<f32>;3]> = Vec::new();
+        for f in self.topology.faces() {
+            let points : Vec<Vector3<f32>> = self.topology.vertices_around(f).map(|v| {
+                let p = position(v);
+                Vector3::new(p[0] as f32,p[1] as f32,p[2] as f32)
+            }).collect();
+            for i in 1..points.len()-1 {
+                triangles.push([points[0],points[i],points[i+1]]);
+            }
+        }
+
+        let mut output = BufWriter::new(output);
+        match format {
+            StlFormat::Ascii => {
+                try!(write!(output,"solid lwmesh\n"));
+                for t in triangles.iter() {
+                    let n = triangle_normal(t[0],t[1],t[2]);
+                    try!(write!(output,"facet normal {} {} {}\n",n.x,n.y,n.z));
+                    try!(write!(output,"outer loop\n"));
+                    for p in t.iter() {
+                        try!(write!(output,"vertex {} {} {}\n",p.x,p.y,p.z));
+                    }
+                    try!(write!(output,"endloop\nendfacet\n"));
+                }
+                try!(write!(output,"endsolid lwmesh\n"));
+            },
+            StlFormat::Binary => {
+                try!(output.write_all(&[0u8;80]));
+                try!(output.write_all(&(triangles.len() as u32).to_le_bytes()));
+                for t in triangles.iter() {
+                    let n = triangle_normal(t[0],t[1],t[2]);
+                    for p in [n,t[0],t[1],t[2]].iter() {
+                        try!(output.write_all(&p.x.to_bits().to_le_bytes()));
+                        try!(output.write_all(&p.y.to_bits().to_le_bytes()));
+                        try!(output.write_all(&p.z.to_bits().to_le_bytes()));
+                    }
+                    try!(output.write_all(&[0u8;2]));
+                }
+            },
+        }
+        try!(output.flush());
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mesh::Mesh;
+    use nalgebra::Vector3;
+    use std::str;
+
+    fn quad() -> Mesh {
+        let mut m = Mesh::new();
+        let pos = m.properties.add_vertex_property::<Vector3<f32>>("v:position",Vector3::new(0f32,0f32,0f32)).unwrap();
+        let v0 = m.add_vertex();
+        m.properties[(pos,v0)] = Vector3::new(0.,0.,0.);
+        let v1 = m.add_vertex();
+        m.properties[(pos,v1)] = Vector3::new(1.,0.,0.);
+        let v2 = m.add_vertex();
+        m.properties[(pos,v2)] = Vector3::new(0.,1.,0.);
+        let v3 = m.add_vertex();
+        m.properties[(pos,v3)] = Vector3::new(1.,1.,0.);
+        m.add_face(&vec![v0,v1,v3,v2]);
+        m
+    }
+
+    #[test]
+    fn write_stl_ascii() {
+        let m = quad();
+        let expected =
+        r#"solid lwmesh
+facet normal 0 0 1
+outer loop
+vertex 0 0 0
+vertex 1 0 0
+vertex 1 1 0
+endloop
+endfacet
+facet normal 0 0 1
+outer loop
+vertex 0 0 0
+vertex 1 1 0
+vertex 0 1 0
+endloop
+endfacet
+endsolid lwmesh
+"#;
+        let mut output = Vec::<u8>::new();
+        assert!(m.write_stl(&mut output,StlFormat::Ascii).is_ok());
+        assert_eq!(expected,str::from_utf8(&output).unwrap());
+    }
+
+    #[test]
+    fn load_write_stl() {
+        let m = Mesh::load("cube.obj").ok().unwrap();
+        for format in vec![StlFormat::Ascii,StlFormat::Binary] {
+            let mut output = Vec::<u8>::new();
+            assert!(m.write_stl(&mut output,format).is_ok());
+            let m2 = Mesh::load_stl(&output[..],0.).ok().unwrap();
+            assert_eq!(m2.topology.n_vertices(),8);
+            assert_eq!(m2.topology.n_faces(),12);
+            assert_eq!(m2.topology.n_edges(),18);
+            for v in m2.topology.vertices() {
+                assert!(!m2.topology.is_boundary_vertex(v));
+            }
+        }
+    }
+
+    #[test]
+    fn load_stl_tolerance() {
+        let input = "solid t\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\n\
+                     endloop\nendfacet\nfacet normal 0 0 1\nouter loop\nvertex 0 1.001 0\nvertex 1.001 0 0\n\
+                     vertex 1 1 0\nendloop\nendfacet\nendsolid t\n";
+        let m = Mesh::load_stl(input.as_bytes(),0.).ok().unwrap();
+        assert_eq!(m.topology.n_vertices(),6);
+        let m = Mesh::load_stl(input.as_bytes(),0.01).ok().unwrap();
+        assert_eq!(m.topology.n_vertices(),4);
+        assert_eq!(m.topology.n_edges(),5);
+        let input = "solid t\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\n\
+                     endloop\nendfacet\nfacet normal 0 0 1\nouter loop\nvertex 0 1 0\nvertex 1 0 0\n\
+                     vertex 1 1 0\nendloop\nendfacet\nendsolid t\n";
+        let m = Mesh::load_stl(input.as_bytes(),-1.).ok().unwrap();
+        assert_eq!(m.topology.n_vertices(),4);
+    }
+
+    #[test]
+    fn load_stl_degenerate() {
+        let input = "solid t\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\n\
+                     endloop\nendfacet\nfacet normal 0 0 1\nouter loop\nvertex 0 1 0\nvertex 1 0 0\n\
+                     vertex 1 0.001 0\nendloop\nendfacet\nendsolid t\n";
+        let m = Mesh::load_stl(input.as_bytes(),0.01).ok().unwrap();
+        assert_eq!(m.topology.n_vertices(),3);
+        assert_eq!(m.topology.n_faces(),1);
+    }
+
+    #[test]
+    fn load_stl_non_manifold() {
+        let input = "solid t\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\n\
+                     endloop\nendfacet\nfacet normal 0 0 -1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\n\
+                     vertex 0 -1 0\nendloop\nendfacet\nfacet normal 0 -1 0\nouter loop\nvertex 0 0 0\n\
+                     vertex 1 0 0\nvertex 0 0 1\nendloop\nendfacet\nendsolid t\n";
+        let m = Mesh::load_stl(input.as_bytes(),0.).ok().unwrap();
+        assert_eq!(m.topology.n_vertices(),5);
+        assert_eq!(m.topology.n_faces(),2);
+    }
+
+    #[test]
+    fn load_stl_invalid() {
+        match Mesh::load_stl("solid t\nfacet normal 0 0 1\nouter loop\nvertex 0 0\n".as_bytes(),0.) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        match Mesh::load_stl(&[0u8;10][..],0.) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+        // a huge triangle count is rejected instead of overflowing the expected size
+        let mut header = [0u8;84];
+        header[80..84].copy_from_slice(&u32::MAX.to_le_bytes());
+        match Mesh::load_stl(&header[..],0.) {
+            Err(MeshLoadingError::InvalidFormat(_)) => {},
+            _ => assert!(false),
+        }
+    }
+}
@@ -0,0 +1,647 @@
+//! Subdivision of triangle meshes.
+
+use std::f64::consts::PI;
+use mesh::*;
+use handle::*;
+use mesh_iterator::{VerticesAround, HalfedgesAround};
+#[cfg(feature = "nalgebra")]
+use nalgebra::{Vector2, Vector3, Vector4};
+
+/// A property type which can be interpolated, i.e. a vector space over the reals.
+pub trait Linear : Clone + 'static {
+    /// Returns the value multiplied by `s`.
+    fn scale(&self, s : f64) -> Self;
+
+    /// Returns the sum of the values.
+    fn add(&self, other : &Self) -> Self;
+}
+
+macro_rules! impl_linear_scalar {
+    ($s:ty) => {
+        impl Linear for $s {
+            fn scale(&self, s : f64) -> $s {
+                *self * s as $s
+            }
+
+            fn add(&self, other : &$s) -> $s {
+                *self + *other
+            }
+        }
+    }
+}
+
+macro_rules! impl_linear_array {
+    ($s:ty, $n:expr) => {
+        impl Linear for [$s;$n] {
+            fn scale(&self, s : f64) -> [$s;$n] {
+                let mut x = *self;
+                for c in x.iter_mut() {
+                    *c *= s as $s;
+                }
+                x
+            }
+
+            fn add(&self, other : &[$s;$n]) -> [$s;$n] {
+                let mut x = *self;
+                for (c,o) in x.iter_mut().zip(other.iter()) {
+                    *c += *o;
+                }
+                x
+            }
+        }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+macro_rules! impl_linear_vector {
+    ($v:ident, $s:ty) => {
+        impl Linear for $v<$s> {
+            fn scale(&self, s : f64) -> $v<$s> {
+                *self * s as $s
+            }
+
+            fn add(&self, other : &$v<$s>) -> $v<$s> {
+                *self + *other
+            }
+        }
+    }
+}
+
+impl_linear_scalar!(f32);
+impl_linear_scalar!(f64);
+impl_linear_array!(f32, 2);
+impl_linear_array!(f32, 3);
+impl_linear_array!(f32, 4);
+impl_linear_array!(f64, 2);
+impl_linear_array!(f64, 3);
+impl_linear_array!(f64, 4);
+#[cfg(feature = "nalgebra")]
+impl_linear_vector!(Vector2, f32);
+#[cfg(feature = "nalgebra")]
+impl_linear_vector!(Vector3, f32);
+#[cfg(feature = "nalgebra")]
+impl_linear_vector!(Vector4, f32);
+#[cfg(feature = "nalgebra")]
+impl_linear_vector!(Vector2, f64);
+#[cfg(feature = "nalgebra")]
+impl_linear_vector!(Vector3, f64);
+#[cfg(feature = "nalgebra")]
+impl_linear_vector!(Vector4, f64);
+
+/// The weighted elements of the original `Mesh` defining an element of the subdivided one.
+type Stencil = Vec<(usize,f64)>;
+
+/// Interpolates the property `name` of the original properties `old` into `new` with the stencils.
+/// Returns `false` if the property does not exist with the type `D`.
+type Interpolation = fn(&str, &Properties, &mut Properties, &[Stencil]) -> bool;
+
+/// Returns `true` if the property `name` exists with the type `D`.
+type Lookup = fn(&str, &Properties) -> bool;
+
+/// A property to interpolate, which must exist if it was requested explicitly.
+#[derive(Clone)]
+struct PropertyInterpolation {
+    name_ : String,
+    interpolation_ : Interpolation,
+    exists_ : Lookup,
+    required_ : bool,
+}
+
+fn combine<'a, D : Linear, I : Iterator<Item = (f64, &'a D)>>(mut values : I) -> Option<D> {
+    let first = match values.next() {
+        Some((w,x)) => x.scale(w),
+        None => return None,
+    };
+    Some(values.fold(first,|sum,(w,x)| sum.add(&x.scale(w))))
+}
+
+fn interpolate_vertices<D : Linear>(name : &str, old : &Properties, new : &mut Properties, stencils : &[Stencil]) -> bool {
+    let p = match old.get_vertex_property::<D>(name) {
+        Some(p) => p,
+        None => return false,
+    };
+    for (i,s) in stencils.iter().enumerate() {
+        if let Some(x) = combine(s.iter().map(|&(j,w)| (w,&old[(p,Vertex::new(j))]))) {
+            new[(p,Vertex::new(i))] = x;
+        }
+    }
+    true
+}
+
+fn has_vertex_property<D : Linear>(name : &str, properties : &Properties) -> bool {
+    properties.get_vertex_property::<D>(name).is_some()
+}
+
+fn has_halfedge_property<D : Linear>(name : &str, properties : &Properties) -> bool {
+    properties.get_halfedge_property::<D>(name).is_some()
+}
+
+fn interpolate_halfedges<D : Linear>(name : &str, old : &Properties, new : &mut Properties, stencils : &[Stencil]) -> bool {
+    let p = match old.get_halfedge_property::<D>(name) {
+        Some(p) => p,
+        None => return false,
+    };
+    for (i,s) in stencils.iter().enumerate() {
+        if let Some(x) = combine(s.iter().map(|&(j,w)| (w,&old[(p,Halfedge::new(j))]))) {
+            new[(p,Halfedge::new(i))] = x;
+        }
+    }
+    true
+}
+
+/// The errors of the subdivision.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SubdivisionError {
+    /// The `Mesh` has faces which are not triangles, see `Mesh::triangulate_all`.
+    NotTriangleMesh,
+    /// A property requested in the `LoopOptions` does not exist with the requested type.
+    MissingProperty(String),
+}
+
+/// Options of the Loop subdivision: the properties to interpolate and the sharp edges.
+///
+/// The vertex positions are always interpolated if their type is `[f32;3]`, `[f64;3]`,
+/// `Vector3<f32>` or `Vector3<f64>`. With nalgebra, the `h:texcoord` and `h:normal` halfedge
+/// properties stored as `Vector3<f32>` by `Mesh::load_obj` are interpolated too when they exist.
+/// The properties added with `interpolate_vertex_property` and `interpolate_halfedge_property`
+/// must exist with the given type, or the subdivision fails with `SubdivisionError::MissingProperty`.
+///
+/// # Examples
+///
+/// ```
+/// use lwmesh::subdivision::LoopOptions;
+///
+/// let options = LoopOptions::new()
+///     .interpolate_vertex_property::<f32>("v:temperature")
+///     .interpolate_halfedge_property::<[f32;2]>("h:uv")
+///     .crease_property("e:sharp");
+/// ```
+#[derive(Clone)]
+pub struct LoopOptions {
+    vertex_properties_ : Vec<PropertyInterpolation>,
+    halfedge_properties_ : Vec<PropertyInterpolation>,
+    crease_ : String,
+}
+
+impl LoopOptions {
+    /// Constructs the default options, with the creases marked by the `bool` edge property `e:crease`.
+    pub fn new() -> LoopOptions {
+        let options = LoopOptions {
+            vertex_properties_ : Vec::new(),
+            halfedge_properties_ : Vec::new(),
+            crease_ : "e:crease".to_string(),
+        };
+        let options = options
+            .interpolate_vertex_property::<[f32;3]>("v:position")
+            .interpolate_vertex_property::<[f64;3]>("v:position");
+        #[cfg(feature = "nalgebra")]
+        let options = options
+            .interpolate_vertex_property::<Vector3<f32>>("v:position")
+            .interpolate_vertex_property::<Vector3<f64>>("v:position")
+            .interpolate_halfedge_property::<Vector3<f32>>("h:texcoord")
+            .interpolate_halfedge_property::<Vector3<f32>>("h:normal");
+        // the default properties are only interpolated when they exist
+        let mut options = options;
+        for p in options.vertex_properties_.iter_mut().chain(options.halfedge_properties_.iter_mut()) {
+            p.required_ = false;
+        }
+        options
+    }
+
+    /// Interpolates the vertex property `name` of type `D` with the Loop weights.
+    ///
+    /// The other vertex properties keep their value on the original vertices, and get their
+    /// default value on the inserted vertices.
+    pub fn interpolate_vertex_property<D : Linear>(mut self, name : &str) -> LoopOptions {
+        self.vertex_properties_.push(PropertyInterpolation {
+            name_ : name.to_string(),
+            interpolation_ : interpolate_vertices::<D>,
+            exists_ : has_vertex_property::<D>,
+            required_ : true,
+        });
+        self
+    }
+
+    /// Interpolates the halfedge property `name` of type `D` linearly in each face, as the
+    /// per corner attributes like the texture coordinates.
+    ///
+    /// The corners of the original faces do not exist anymore after the subdivision, so the
+    /// other halfedge properties are reset to their default value.
+    pub fn interpolate_halfedge_property<D : Linear>(mut self, name : &str) -> LoopOptions {
+        self.halfedge_properties_.push(PropertyInterpolation {
+            name_ : name.to_string(),
+            interpolation_ : interpolate_halfedges::<D>,
+            exists_ : has_halfedge_property::<D>,
+            required_ : true,
+        });
+        self
+    }
+
+    /// Uses the `bool` edge property `name` to mark the crease edges.
+    pub fn crease_property(mut self, name : &str) -> LoopOptions {
+        self.crease_ = name.to_string();
+        self
+    }
+}
+
+impl Default for LoopOptions {
+    fn default() -> LoopOptions {
+        LoopOptions::new()
+    }
+}
+
+/// The stencils of the vertices of the subdivided `Mesh` in terms of the original vertices.
+struct LoopStencils {
+    vertices_ : Vec<Stencil>,
+    edges_ : Vec<(Edge,Stencil)>,
+}
+
+/// Computes the Loop stencils of the original vertices and of the points inserted on the edges.
+///
+/// The boundary and crease edges are sharp. A vertex with two sharp edges follows the crease,
+/// a vertex with more sharp edges is a corner and does not move.
+fn loop_stencils(m : &Mesh, is_sharp : &Fn(Edge) -> bool) -> LoopStencils {
+    let mut vertices = vec![Vec::new(); m.topology.vertices_size()];
+    for v in m.topology.vertices() {
+        let sharp : Vec<Vertex> = m.topology.halfedges_around(v)
+            .filter(|&h| is_sharp(m.topology.edge(h)))
+            .map(|h| m.topology.to_vertex(h))
+            .collect();
+        vertices[v.idx()] = match sharp.len() {
+            0 | 1 => {
+                let ring : Vec<Vertex> = m.topology.vertices_around(v).collect();
+                let n = ring.len() as f64;
+                if ring.is_empty() {
+                    vec![(v.idx(),1.)]
+                } else {
+                    let tmp = 3. / 8. + (2. * PI / n).cos() / 4.;
+                    let beta = (5. / 8. - tmp * tmp) / n;
+                    let mut s = vec![(v.idx(),1. - n * beta)];
+                    s.extend(ring.iter().map(|w| (w.idx(),beta)));
+                    s
+                }
+            },
+            2 => vec![(v.idx(),0.75),(sharp[0].idx(),0.125),(sharp[1].idx(),0.125)],
+            _ => vec![(v.idx(),1.)],
+        };
+    }
+
+    let edges = m.topology.edges().map(|e| {
+        let h = m.topology.edge_halfedge(e,0);
+        let o = m.topology.edge_halfedge(e,1);
+        let v0 = m.topology.to_vertex(h);
+        let v1 = m.topology.to_vertex(o);
+        let s = if is_sharp(e) {
+            vec![(v0.idx(),0.5),(v1.idx(),0.5)]
+        } else {
+            let v2 = m.topology.to_vertex(m.topology.next_halfedge(h));
+            let v3 = m.topology.to_vertex(m.topology.next_halfedge(o));
+            vec![(v0.idx(),0.375),(v1.idx(),0.375),(v2.idx(),0.125),(v3.idx(),0.125)]
+        };
+        (e,s)
+    }).collect();
+
+    LoopStencils {
+        vertices_ : vertices,
+        edges_ : edges,
+    }
+}
+
+/// Subdivides the triangle `Mesh` m with Loop's scheme, in place.
+///
+/// Every edge is split at a new vertex and every triangle is replaced by four triangles.
+/// The original vertices keep their handles. The boundary edges and the crease edges are kept
+/// sharp, and their halves are crease edges too: all the edge properties of an edge are copied
+/// to its halves, and the face properties of a triangle to its four children. The properties
+/// listed in `options` are interpolated, the halfedge properties which are not are reset to
+/// their default value. If a property requested in `options` is missing, the `Mesh` is left
+/// unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use lwmesh::*;
+/// use lwmesh::subdivision::*;
+///
+/// let mut m = Mesh::new();
+/// let pos = m.add_positions::<[f64;3]>().unwrap();
+/// let vvec = m.add_vertices(4);
+/// m.properties[(pos,vvec[1])] = [1.,0.,0.];
+/// m.properties[(pos,vvec[2])] = [0.,1.,0.];
+/// m.properties[(pos,vvec[3])] = [0.,0.,1.];
+/// for &(a,b,c) in &[(0,2,1),(0,1,3),(1,2,3),(0,3,2)] {
+///     m.add_face(&vec![vvec[a],vvec[b],vvec[c]]).unwrap();
+/// }
+/// loop_subdivide_in_place(&mut m,&LoopOptions::new()).unwrap();
+/// assert_eq!(m.topology.n_vertices(),4 + 6);
+/// assert_eq!(m.topology.n_edges(),2 * 6 + 3 * 4);
+/// assert_eq!(m.topology.n_faces(),4 * 4);
+/// ```
+pub fn loop_subdivide_in_place(m : &mut Mesh, options : &LoopOptions) -> Result<(),SubdivisionError> {
+    if !m.topology.is_triangle_mesh() {
+        return Err(SubdivisionError::NotTriangleMesh);
+    }
+    for p in options.vertex_properties_.iter().chain(options.halfedge_properties_.iter()) {
+        if p.required_ && !(p.exists_)(&p.name_,&m.properties) {
+            return Err(SubdivisionError::MissingProperty(p.name_.clone()));
+        }
+    }
+    let old = m.properties.clone();
+    let crease = m.properties.get_edge_property::<bool>(&options.crease_);
+    let stencils = {
+        let topology = &m.topology;
+        let properties = &m.properties;
+        let is_sharp = |e : Edge| topology.is_boundary_edge(e) || match crease {
+            Some(p) => properties[(p,e)],
+            None => false,
+        };
+        loop_stencils(m,&is_sharp)
+    };
+
+    // the original corner of each face at each of its vertices
+    let mut corners = vec![Vec::new(); m.topology.faces_size()];
+    for f in m.topology.faces() {
+        corners[f.idx()] = m.topology.halfedges_around(f).map(|h| (m.topology.to_vertex(h),h)).collect();
+    }
+    let mut parents : Vec<Face> = (0..m.topology.faces_size()).map(Face::new).collect();
+
+    // split the edges
+    let n_vertices = m.topology.vertices_size();
+    let n_edges = m.topology.edges_size();
+    let mut is_half = vec![true; n_edges];
+    let mut endpoints = Vec::with_capacity(stencils.edges_.len());
+    for &(e,_) in &stencils.edges_ {
+        let h = m.topology.edge_halfedge(e,0);
+        let o = m.topology.edge_halfedge(e,1);
+        endpoints.push((m.topology.from_vertex(h),m.topology.to_vertex(h)));
+        let f0 = m.topology.face(h);
+        let f3 = m.topology.face(o);
+
+        let v = m.add_vertex();
+        let first_face = m.topology.faces_size();
+        let t = m.split_edge(e,v);
+        let half = m.topology.edge(t);
+        m.properties.copy_edge_properties(e,half);
+        is_half.resize(m.topology.edges_size(),false);
+        is_half[half.idx()] = true;
+
+        // split_edge creates the new face of f0 first, then the one of f3
+        for (new_face,f) in (first_face..).zip(f0.into_iter().chain(f3)) {
+            m.properties.copy_face_properties(f,Face::new(new_face));
+            parents.push(parents[f.idx()]);
+        }
+    }
+
+    // flip the edges joining an original vertex to an inserted one across an original triangle
+    for (i,&half) in is_half.iter().enumerate().skip(n_edges) {
+        if half {
+            continue;
+        }
+        let e = Edge::new(i);
+        let h = m.topology.edge_halfedge(e,0);
+        let inserted0 = m.topology.from_vertex(h).idx() >= n_vertices;
+        let inserted1 = m.topology.to_vertex(h).idx() >= n_vertices;
+        if inserted0 != inserted1 && m.topology.is_flip_ok(e) {
+            m.topology.flip(e);
+        }
+    }
+
+    // interpolate the vertex properties
+    let mut vertex_stencils = stencils.vertices_;
+    vertex_stencils.extend(stencils.edges_.into_iter().map(|(_,s)| s));
+    for p in &options.vertex_properties_ {
+        (p.interpolation_)(&p.name_,&old,&mut m.properties,&vertex_stencils);
+    }
+
+    // interpolate the corner properties in each original face
+    let mut interpolated : Vec<&str> = Vec::new();
+    if options.halfedge_properties_.iter().any(|p| (p.exists_)(&p.name_,&old)) {
+        let corner = |f : Face, v : Vertex| corners[f.idx()].iter().find(|&&(w,_)| w == v).unwrap().1.idx();
+        let mut halfedge_stencils = vec![Vec::new(); m.topology.halfedges_size()];
+        for h in m.topology.halfedges() {
+            let f = match m.topology.face(h) {
+                Some(f) => parents[f.idx()],
+                None => continue,
+            };
+            let v = m.topology.to_vertex(h);
+            halfedge_stencils[h.idx()] = if v.idx() < n_vertices {
+                vec![(corner(f,v),1.)]
+            } else {
+                let (a,b) = endpoints[v.idx() - n_vertices];
+                vec![(corner(f,a),0.5),(corner(f,b),0.5)]
+            };
+        }
+        for p in &options.halfedge_properties_ {
+            if (p.interpolation_)(&p.name_,&old,&mut m.properties,&halfedge_stencils) {
+                interpolated.push(&p.name_);
+            }
+        }
+    }
+
+    // the values of the other corner properties are meaningless on the new corners
+    let reset : Vec<String> = m.properties.halfedge_property_names().into_iter()
+        .filter(|name| !interpolated.contains(name))
+        .map(String::from)
+        .collect();
+    for name in reset {
+        m.properties.reset_halfedge_property(&name);
+    }
+    Ok(())
+}
+
+/// Returns the triangle `Mesh` m subdivided with Loop's scheme, see `loop_subdivide_in_place`.
+///
+/// # Examples
+///
+/// ```
+/// use lwmesh::*;
+/// use lwmesh::subdivision::*;
+///
+/// let mut m = Mesh::new();
+/// let pos = m.add_positions::<[f64;3]>().unwrap();
+/// let vvec = m.add_vertices(4);
+/// m.properties[(pos,vvec[1])] = [1.,0.,0.];
+/// m.properties[(pos,vvec[2])] = [0.,1.,0.];
+/// m.properties[(pos,vvec[3])] = [0.,0.,1.];
+/// for &(a,b,c) in &[(0,2,1),(0,1,3),(1,2,3),(0,3,2)] {
+///     m.add_face(&vec![vvec[a],vvec[b],vvec[c]]).unwrap();
+/// }
+/// let subdivided = loop_subdivide(&m,&LoopOptions::new()).unwrap();
+/// assert_eq!(subdivided.topology.n_faces(),4 * m.topology.n_faces());
+/// assert!(subdivided.topology.is_closed());
+/// ```
+pub fn loop_subdivide(m : &Mesh, options : &LoopOptions) -> Result<Mesh,SubdivisionError> {
+    let mut subdivided = m.clone();
+    try!(loop_subdivide_in_place(&mut subdivided,options));
+    Ok(subdivided)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mesh::Mesh;
+
+    /// Builds a square made of 8 triangles around its center vertex 4, with the positions
+    /// on the plane z = 0 except the center at z = 1.
+    fn pyramid() -> Mesh {
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f64;3]>().unwrap();
+        let vvec = m.add_vertices(9);
+        for i in 0..3 {
+            for j in 0..3 {
+                m.properties[(pos,vvec[3*i + j])] = [j as f64,i as f64,if i == 1 && j == 1 {1.} else {0.}];
+            }
+        }
+        for &(a,b,c) in &[(0,1,4),(1,2,4),(2,5,4),(5,8,4),(8,7,4),(7,6,4),(6,3,4),(3,0,4)] {
+            m.add_face(&vec![vvec[a],vvec[b],vvec[c]]).unwrap();
+        }
+        m
+    }
+
+    #[test]
+    fn subdivide_topology() {
+        let mut m = pyramid();
+        loop_subdivide_in_place(&mut m,&LoopOptions::new()).unwrap();
+        assert_eq!(m.topology.n_vertices(),9 + 16);
+        assert_eq!(m.topology.n_edges(),2 * 16 + 3 * 8);
+        assert_eq!(m.topology.n_faces(),32);
+        assert!(m.topology.is_triangle_mesh());
+        assert_eq!(m.topology.validate().errors,vec![]);
+        assert_eq!(m.topology.boundary_loops()[0].len(),16);
+        // every inserted vertex is linked to inserted vertices only, except its two original halves
+        for v in m.topology.vertices().filter(|v| v.idx() >= 9) {
+            let originals = m.topology.vertices_around(v).filter(|w| w.idx() < 9).count();
+            assert_eq!(originals,2);
+        }
+    }
+
+    #[test]
+    fn subdivide_positions() {
+        let m = pyramid();
+        let s = loop_subdivide(&m,&LoopOptions::new()).unwrap();
+        let pos = s.positions::<[f64;3]>().unwrap();
+        let z = |v : usize| s.properties[(pos,Vertex::new(v))][2];
+
+        // boundary vertices and boundary edge points stay on the plane
+        for v in s.topology.vertices().filter(|&v| s.topology.is_boundary_vertex(v)) {
+            assert_eq!(s.properties[(pos,v)][2],0.);
+        }
+        // the center is smoothed with the Loop weights of valence 8
+        let tmp = 3. / 8. + (2. * PI / 8.).cos() / 4.;
+        let beta = (5. / 8. - tmp * tmp) / 8.;
+        assert!((z(4) - (1. - 8. * beta)).abs() < 1e-12);
+        // the corner (0,0) follows the boundary
+        assert_eq!(s.properties[(pos,Vertex::new(0))],[0.125,0.125,0.]);
+        // the point of the interior edge (0,4) weights its endpoints by 3/8 and its opposite vertices by 1/8
+        let e = s.topology.vertices().find(|&v| {
+            v.idx() >= 9 && s.topology.vertices_around(v).any(|w| w.idx() == 0) && s.topology.vertices_around(v).any(|w| w.idx() == 4)
+        }).unwrap();
+        assert_eq!(s.properties[(pos,e)],[0.5,0.5,0.375]);
+    }
+
+    #[test]
+    fn creases_and_properties() {
+        let mut m = pyramid();
+        let crease = m.properties.add_edge_property::<bool>("e:crease",false).unwrap();
+        let name = m.properties.add_face_property::<String>("f:name",String::new()).unwrap();
+        let texcoord = m.properties.add_halfedge_property::<[f32;2]>("h:texcoord",[0.,0.]).unwrap();
+        let id = m.properties.add_halfedge_property::<u32>("h:id",0).unwrap();
+        let pos = m.positions::<[f64;3]>().unwrap();
+        let faces : Vec<Face> = m.topology.faces().collect();
+        for f in faces {
+            m.properties[(name,f)] = format!("f{}",f.idx());
+            let halfedges : Vec<Halfedge> = m.topology.halfedges_around(f).collect();
+            for h in halfedges {
+                let p = m.properties[(pos,m.topology.to_vertex(h))];
+                m.properties[(texcoord,h)] = [p[0] as f32,p[1] as f32];
+                m.properties[(id,h)] = 7;
+            }
+        }
+        // crease along the diagonal from (0,0) to (2,2)
+        for &(a,b) in &[(0,4),(4,8)] {
+            let h = m.topology.find_halfedge(Vertex::new(a),Vertex::new(b)).unwrap();
+            let e = m.topology.edge(h);
+            m.properties[(crease,e)] = true;
+        }
+
+        let options = LoopOptions::new().interpolate_halfedge_property::<[f32;2]>("h:texcoord");
+        loop_subdivide_in_place(&mut m,&options).unwrap();
+
+        // the center follows the crease
+        assert_eq!(m.properties[(pos,Vertex::new(4))],[1.,1.,0.75]);
+        let creases = m.topology.edges().filter(|&e| m.properties[(crease,e)]).count();
+        assert_eq!(creases,4);
+
+        for f in m.topology.faces() {
+            assert!(m.properties[(name,f)].starts_with("f"));
+            // the texture coordinates are the planar coordinates of the original positions
+            let halfedges : Vec<Halfedge> = m.topology.halfedges_around(f).collect();
+            for h in halfedges {
+                let v = m.topology.to_vertex(h);
+                let t = m.properties[(texcoord,h)];
+                if v.idx() < 9 {
+                    assert_eq!(t,[(v.idx() % 3) as f32,(v.idx() / 3) as f32]);
+                } else {
+                    assert!(t[0] >= 0. && t[0] <= 2. && t[1] >= 0. && t[1] <= 2. && (2. * t[0]).fract() == 0.);
+                }
+            }
+        }
+        let names = m.topology.faces().filter(|&f| m.properties[(name,f)] == "f0").count();
+        assert_eq!(names,4);
+    }
+
+    #[test]
+    fn reset_halfedge_properties() {
+        let mut m = pyramid();
+        let id = m.properties.add_halfedge_property::<u32>("h:id",0).unwrap();
+        let halfedges : Vec<Halfedge> = m.topology.halfedges().collect();
+        for h in halfedges {
+            m.properties[(id,h)] = 7;
+        }
+        loop_subdivide_in_place(&mut m,&LoopOptions::new()).unwrap();
+        // the corner properties which are not interpolated do not keep stale values
+        assert!(m.topology.halfedges().all(|h| m.properties[(id,h)] == 0));
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn default_corner_properties() {
+        let mut m = pyramid();
+        let texcoord = m.properties.add_halfedge_property::<Vector3<f32>>("h:texcoord",Vector3::new(0.,0.,0.)).unwrap();
+        let pos = m.positions::<[f64;3]>().unwrap();
+        let halfedges : Vec<Halfedge> = m.topology.halfedges().filter(|&h| m.topology.face(h).is_some()).collect();
+        for h in halfedges {
+            let p = m.properties[(pos,m.topology.to_vertex(h))];
+            m.properties[(texcoord,h)] = Vector3::new(p[0] as f32,p[1] as f32,0.);
+        }
+        loop_subdivide_in_place(&mut m,&LoopOptions::new()).unwrap();
+        // the texture coordinates loaded from OBJ files are interpolated without being requested
+        for h in m.topology.halfedges().filter(|&h| m.topology.face(h).is_some()) {
+            let v = m.topology.to_vertex(h);
+            let t = m.properties[(texcoord,h)];
+            if v.idx() < 9 {
+                assert_eq!(t,Vector3::new((v.idx() % 3) as f32,(v.idx() / 3) as f32,0.));
+            } else {
+                assert!(t.x >= 0. && t.x <= 2. && t.y >= 0. && t.y <= 2. && (2. * t.x).fract() == 0. && (2. * t.y).fract() == 0.);
+            }
+        }
+    }
+
+    #[test]
+    fn missing_property() {
+        let mut m = pyramid();
+        let options = LoopOptions::new().interpolate_halfedge_property::<[f32;2]>("h:uv");
+        assert_eq!(loop_subdivide_in_place(&mut m,&options),Err(SubdivisionError::MissingProperty("h:uv".to_string())));
+        let options = LoopOptions::new().interpolate_vertex_property::<f32>("v:position");
+        assert_eq!(loop_subdivide_in_place(&mut m,&options),Err(SubdivisionError::MissingProperty("v:position".to_string())));
+        assert_eq!(m.topology.n_faces(),8);
+    }
+
+    #[test]
+    fn not_triangle_mesh() {
+        let mut m = Mesh::new();
+        let vvec = m.add_vertices(4);
+        m.add_face(&vvec).unwrap();
+        assert!(loop_subdivide(&m,&LoopOptions::new()).err() == Some(SubdivisionError::NotTriangleMesh));
+    }
+}
@@ -0,0 +1,276 @@
+use mesh::*;
+use handle::*;
+use geometry::{sub, add, cross, dot, norm, normalized, angle};
+use mesh_iterator::HalfedgesAround;
+
+/// Returns the 2D cross product of `b-a` and `c-b`, positive if `a`, `b`, `c` turn left.
+fn turn(a : [f64;2], b : [f64;2], c : [f64;2]) -> f64 {
+    (b[0]-a[0]) * (c[1]-b[1]) - (b[1]-a[1]) * (c[0]-b[0])
+}
+
+/// Returns `true` if `p` is inside the counter-clockwise triangle `(a,b,c)` or on its boundary.
+fn in_triangle(p : [f64;2], a : [f64;2], b : [f64;2], c : [f64;2]) -> bool {
+    turn(a,b,p) >= 0. && turn(b,c,p) >= 0. && turn(c,a,p) >= 0.
+}
+
+/// Returns the smallest angle of the triangle `(a,b,c)`.
+fn min_angle(a : [f64;2], b : [f64;2], c : [f64;2]) -> f64 {
+    let corner = |p : [f64;2], q : [f64;2], r : [f64;2]| {
+        let u = normalized([q[0]-p[0],q[1]-p[1],0.]);
+        let v = normalized([r[0]-p[0],r[1]-p[1],0.]);
+        angle(u,v)
+    };
+    corner(a,b,c).min(corner(b,c,a)).min(corner(c,a,b))
+}
+
+impl Topology {
+    /// Returns `true` if all the faces of the `Mesh` are triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]).unwrap();
+    /// assert!(m.topology.is_triangle_mesh());
+    /// assert!(!m.topology.is_quad_mesh());
+    /// ```
+    pub fn is_triangle_mesh(&self) -> bool {
+        self.faces().all(|f| self.halfedges_around(f).count() == 3)
+    }
+
+    /// Returns `true` if all the faces of the `Mesh` are quads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// m.add_face(&vvec).unwrap();
+    /// assert!(m.topology.is_quad_mesh());
+    /// ```
+    pub fn is_quad_mesh(&self) -> bool {
+        self.faces().all(|f| self.halfedges_around(f).count() == 4)
+    }
+}
+
+impl Mesh {
+    /// Returns the corners of a polygon projected on its plane, if the `Mesh` has positions
+    /// and the polygon is not degenerate.
+    fn project_polygon(&self, corners : &[Vertex]) -> Option<Vec<[f64;2]>> {
+        let position = match self.coords_reader() {
+            Some(x) => x,
+            None => return None,
+        };
+        let points : Vec<[f64;3]> = corners.iter().map(|&v| position(v)).collect();
+        let mut normal = [0.;3];
+        for i in 0..points.len() {
+            normal = add(normal,cross(points[i],points[(i+1) % points.len()]));
+        }
+        if norm(normal) == 0. {
+            return None;
+        }
+        let normal = normalized(normal);
+
+        // (u,v,normal) is a direct frame, so the polygon stays counter-clockwise
+        let axis = if normal[0].abs() < 0.5 {[1.,0.,0.]} else {[0.,1.,0.]};
+        let u = normalized(cross(normal,axis));
+        let v = cross(normal,u);
+        Some(points.iter().map(|&p| {
+            let d = sub(p,points[0]);
+            [dot(d,u),dot(d,v)]
+        }).collect())
+    }
+
+    /// Splits the `Face` f into triangles by ear clipping, and returns them, starting with f.
+    ///
+    /// The polygon is projected on its plane, and the ears with the largest smallest angle are
+    /// cut first. Without vertex positions, the polygon is assumed convex. The properties of f
+    /// are copied to the new faces, and the properties of the halfedges pointing to each corner
+    /// are copied to the new halfedges pointing to the same corner. An ear is never cut if its
+    /// diagonal already is an edge of the `Mesh`, so the polygon may not be fully triangulated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let prop = m.properties.add_face_property::<u32>("f:my_prop",0).unwrap();
+    /// let vvec = m.add_vertices(5);
+    /// let f = m.add_face(&vvec).unwrap();
+    /// m.properties[(prop,f)] = 42;
+    ///
+    /// let triangles = m.triangulate(f);
+    /// assert_eq!(triangles.len(),3);
+    /// assert!(m.topology.is_triangle_mesh());
+    /// for t in triangles {
+    ///     assert_eq!(m.properties[(prop,t)],42);
+    /// }
+    /// ```
+    pub fn triangulate(&mut self, f : Face) -> Vec<Face> {
+        let corners : Vec<Vertex> = self.topology.halfedges_around(f).map(|h| self.topology.from_vertex(h)).collect();
+        let points = self.project_polygon(&corners);
+
+        let mut triangles = Vec::new();
+        let mut current = f;
+        let mut remaining : Vec<usize> = (0..corners.len()).collect();
+        while remaining.len() > 3 {
+            let n = remaining.len();
+            // the best ear as (is convex and empty, smallest angle, position in remaining)
+            let mut best : Option<(bool,f64,usize)> = None;
+            for p in 0..n {
+                let a = remaining[(p+n-1) % n];
+                let b = remaining[p];
+                let c = remaining[(p+1) % n];
+                if corners[a] == corners[c] || self.topology.find_halfedge(corners[a],corners[c]).is_some() {
+                    continue;
+                }
+                let (valid,quality) = match points {
+                    Some(ref x) => {
+                        let convex = turn(x[a],x[b],x[c]) > 0.;
+                        let empty = remaining.iter().all(|&i| {
+                            i == a || i == b || i == c || x[i] == x[a] || x[i] == x[b] || x[i] == x[c]
+                                || !in_triangle(x[i],x[a],x[b],x[c])
+                        });
+                        (convex && empty,if convex {min_angle(x[a],x[b],x[c])} else {0.})
+                    },
+                    None => (true,0.),
+                };
+                let better = match best {
+                    Some((best_valid,best_quality,_)) => (valid,quality) > (best_valid,best_quality),
+                    None => true,
+                };
+                if better {
+                    best = Some((valid,quality,p));
+                }
+            }
+            let p = match best {
+                Some((_,_,p)) => p,
+                None => break,
+            };
+
+            let a = corners[remaining[(p+n-1) % n]];
+            let b = corners[remaining[p]];
+            let hbc = self.topology.halfedges_around(current).find(|&h| self.topology.from_vertex(h) == b).unwrap();
+            let hab = self.topology.prev_halfedge(hbc);
+            let h1 = self.topology.prev_halfedge(hab);
+            debug_assert!(self.topology.to_vertex(h1) == a);
+
+            // the ear keeps the current face, the rest of the polygon is a new face
+            let hca = self.insert_edge(hbc,h1);
+            let hac = self.topology.opposite_halfedge(hca);
+            self.properties.copy_halfedge_properties(h1,hca);
+            self.properties.copy_halfedge_properties(hbc,hac);
+            let rest = self.topology.face(hac).unwrap();
+            self.properties.copy_face_properties(current,rest);
+
+            triangles.push(current);
+            current = rest;
+            remaining.remove(p);
+        }
+        triangles.push(current);
+        triangles
+    }
+
+    /// Splits every face of the `Mesh` into triangles with `triangulate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(6);
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[4],vvec[3]]).unwrap();
+    /// m.add_face(&vec![vvec[1],vvec[2],vvec[5],vvec[4]]).unwrap();
+    /// assert!(m.topology.is_quad_mesh());
+    ///
+    /// m.triangulate_all();
+    /// assert!(m.topology.is_triangle_mesh());
+    /// assert_eq!(m.topology.n_faces(),4);
+    /// ```
+    pub fn triangulate_all(&mut self) {
+        let faces : Vec<Face> = self.topology.faces().collect();
+        for f in faces {
+            if self.topology.halfedges_around(f).count() > 3 {
+                self.triangulate(f);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mesh::*;
+    use handle::*;
+    use mesh_iterator::{HalfedgesAround, VerticesAround};
+
+    /// Returns the signed area of the triangle `f` in the plane z = 0.
+    fn signed_area(m : &Mesh, pos : PropertyVertex<[f32;3]>, f : Face) -> f32 {
+        let p : Vec<[f32;3]> = m.topology.vertices_around(f).map(|v| m.properties[(pos,v)]).collect();
+        0.5 * ((p[1][0]-p[0][0]) * (p[2][1]-p[0][1]) - (p[1][1]-p[0][1]) * (p[2][0]-p[0][0]))
+    }
+
+    #[test]
+    fn triangulate_concave_polygon() {
+        // an L shaped hexagon, whose corner at (1,1) is reflex
+        let mut m = Mesh::new();
+        let pos = m.add_positions::<[f32;3]>().unwrap();
+        let corners = [[0.,0.,0.],[2.,0.,0.],[2.,1.,0.],[1.,1.,0.],[1.,2.,0.],[0.,2.,0.]];
+        let vvec = m.add_vertices(6);
+        for i in 0..6 {
+            m.properties[(pos,vvec[i])] = corners[i];
+        }
+        let corner = m.properties.add_halfedge_property::<usize>("h:corner",0).unwrap();
+        let f = m.add_face(&vvec).unwrap();
+        let halfedges : Vec<Halfedge> = m.topology.halfedges_around(f).collect();
+        for h in halfedges {
+            m.properties[(corner,h)] = m.topology.to_vertex(h).idx();
+        }
+
+        let triangles = m.triangulate(f);
+        assert_eq!(triangles.len(),4);
+        assert!(triangles[0] == f);
+        assert!(m.topology.is_triangle_mesh());
+        assert_eq!(m.topology.validate().errors,vec![]);
+        let mut area = 0.;
+        for &t in &triangles {
+            let a = signed_area(&m,pos,t);
+            assert!(a > 0.);
+            area += a;
+            for h in m.topology.halfedges_around(t) {
+                assert_eq!(m.properties[(corner,h)],m.topology.to_vertex(h).idx());
+            }
+        }
+        assert_eq!(area,3.);
+    }
+
+    #[test]
+    fn triangulate_all() {
+        let mut m = Mesh::new();
+        let prop = m.properties.add_face_property::<String>("f:name",String::new()).unwrap();
+        let vvec = m.add_vertices(8);
+        for i in 0..3 {
+            let f = m.add_face(&vec![vvec[2*i],vvec[2*i+2],vvec[2*i+3],vvec[2*i+1]]).unwrap();
+            m.properties[(prop,f)] = format!("quad {}",i);
+        }
+        let vtriangle = m.add_vertex();
+        let t = m.add_face(&vec![vvec[7],vvec[6],vtriangle]).unwrap();
+        m.properties[(prop,t)] = "triangle".to_string();
+        assert!(!m.topology.is_triangle_mesh() && !m.topology.is_quad_mesh());
+
+        m.triangulate_all();
+        assert!(m.topology.is_triangle_mesh());
+        assert_eq!(m.topology.n_faces(),7);
+        assert_eq!(m.topology.validate().errors,vec![]);
+        let mut names : Vec<String> = m.topology.faces().map(|f| m.properties[(prop,f)].clone()).collect();
+        names.sort();
+        assert_eq!(names,vec!["quad 0","quad 0","quad 1","quad 1","quad 2","quad 2","triangle"]);
+    }
+}
@@ -0,0 +1,167 @@
+use mesh::*;
+use handle::*;
+
+/// A violation of the invariants of the halfedge data structure.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TopologyError {
+    /// The halfedge references a vertex, face or halfedge which does not exist or is deleted.
+    InvalidReference(Halfedge),
+    /// `prev_halfedge(next_halfedge(h))` is not `h`.
+    NextPrevMismatch(Halfedge),
+    /// The halfedge does not start at the vertex its opposite halfedge points to.
+    /// As the start of a halfedge is the end of the previous one, this also detects
+    /// a halfedge whose next halfedge does not start where it ends.
+    OppositeMismatch(Halfedge),
+    /// The halfedge and its next halfedge do not belong to the same face.
+    FaceMismatch(Halfedge),
+    /// Both halfedges of the edge are boundary halfedges.
+    IsolatedEdge(Edge),
+    /// The halfedge of the face does not belong to it.
+    FaceHalfedgeMismatch(Face),
+    /// Following the next halfedges from the halfedge of the face does not come back to it.
+    OpenFaceLoop(Face),
+    /// The outgoing halfedge of the vertex does not start at it.
+    VertexHalfedgeMismatch(Vertex),
+    /// Rotating around the vertex does not come back to its outgoing halfedge.
+    OpenVertexFan(Vertex),
+    /// The vertex is on the boundary but its outgoing halfedge is not a boundary halfedge.
+    BoundaryHalfedgeNotOutgoing(Vertex),
+}
+
+/// The result of `Topology::validate`, listing the violated invariants.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub errors : Vec<TopologyError>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no invariant is violated.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl Topology {
+    /// Returns `true` if `h` exists and is not deleted.
+    fn is_valid_halfedge(&self, h : Halfedge) -> bool {
+        h.idx() < self.halfedges_size() && !self.is_deleted_halfedge(h)
+    }
+
+    /// Returns `true` if the references of the halfedge `h` can be followed.
+    fn has_valid_references(&self, h : Halfedge) -> bool {
+        let v = self.to_vertex(h);
+        let face_ok = match self.face(h) {
+            Some(f) => f.idx() < self.faces_size() && !self.is_deleted_face(f),
+            None => true,
+        };
+        face_ok && v.idx() < self.vertices_size() && !self.is_deleted_vertex(v)
+            && self.is_valid_halfedge(self.next_halfedge(h)) && self.is_valid_halfedge(self.prev_halfedge(h))
+    }
+
+    /// Checks the invariants of the halfedge data structure maintained by the `Mesh` operations.
+    ///
+    /// Every halfedge must be linked to its next and previous halfedges consistently, start at the
+    /// vertex its opposite points to, and share its face with its next halfedge. The halfedge loop
+    /// of each face must close, and the outgoing halfedge of a boundary vertex must be a boundary
+    /// halfedge. The deleted elements are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lwmesh::*;
+    ///
+    /// let mut m = Mesh::new();
+    /// let vvec = m.add_vertices(4);
+    /// m.add_face(&vec![vvec[0],vvec[1],vvec[2]]).unwrap();
+    /// m.add_face(&vec![vvec[2],vvec[1],vvec[3]]).unwrap();
+    /// let report = m.topology.validate();
+    /// assert!(report.is_valid());
+    /// ```
+    pub fn validate(&self) -> ValidationReport {
+        let mut errors = Vec::new();
+
+        for h in self.halfedges() {
+            if !self.has_valid_references(h) {
+                errors.push(TopologyError::InvalidReference(h));
+                continue;
+            }
+            let next = self.next_halfedge(h);
+            if self.prev_halfedge(next) != h {
+                errors.push(TopologyError::NextPrevMismatch(h));
+            }
+            if self.has_valid_references(self.prev_halfedge(h)) && self.to_vertex(self.opposite_halfedge(h)) != self.from_vertex(h) {
+                errors.push(TopologyError::OppositeMismatch(h));
+            }
+            if self.face(next) != self.face(h) {
+                errors.push(TopologyError::FaceMismatch(h));
+            }
+        }
+
+        for e in self.edges() {
+            if self.is_boundary_halfedge(self.edge_halfedge(e,0)) && self.is_boundary_halfedge(self.edge_halfedge(e,1)) {
+                errors.push(TopologyError::IsolatedEdge(e));
+            }
+        }
+
+        let max_steps = self.halfedges_size();
+        for f in self.faces() {
+            let h0 = self.face_halfedge(f);
+            if !self.is_valid_halfedge(h0) || self.face(h0) != Some(f) {
+                errors.push(TopologyError::FaceHalfedgeMismatch(f));
+                continue;
+            }
+            let mut h = h0;
+            let mut closed = false;
+            for _ in 0..max_steps {
+                if !self.has_valid_references(h) || self.face(h) != Some(f) {
+                    break;
+                }
+                h = self.next_halfedge(h);
+                if h == h0 {
+                    closed = true;
+                    break;
+                }
+            }
+            if !closed {
+                errors.push(TopologyError::OpenFaceLoop(f));
+            }
+        }
+
+        for v in self.vertices() {
+            let h0 = match self.halfedge(v) {
+                Some(h) => h,
+                None => continue,
+            };
+            if !self.is_valid_halfedge(h0) || !self.has_valid_references(h0) || self.from_vertex(h0) != v {
+                errors.push(TopologyError::VertexHalfedgeMismatch(v));
+                continue;
+            }
+            let mut h = h0;
+            let mut closed = false;
+            let mut boundary = false;
+            for _ in 0..max_steps {
+                boundary = boundary || self.is_boundary_halfedge(h);
+                if !self.has_valid_references(self.opposite_halfedge(h)) {
+                    break;
+                }
+                h = self.cw_rotated_halfedge(h);
+                if h == h0 {
+                    closed = true;
+                    break;
+                }
+                if !self.has_valid_references(h) || self.from_vertex(h) != v {
+                    break;
+                }
+            }
+            if !closed {
+                errors.push(TopologyError::OpenVertexFan(v));
+            } else if boundary && !self.is_boundary_halfedge(h0) {
+                errors.push(TopologyError::BoundaryHalfedgeNotOutgoing(v));
+            }
+        }
+
+        ValidationReport {
+            errors : errors,
+        }
+    }
+}